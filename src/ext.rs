@@ -0,0 +1,53 @@
+use crate::{Span, SpanError};
+
+/// Extension methods for slicing a `str` with a `Span`.
+pub trait StrSpanExt {
+    /// Gets the part of the string covered by `span`, with `start` and `end` corresponding to char indexes. This is
+    /// the same as [`Span::apply`].
+    ///
+    /// # Panics
+    /// Panics if the string is shorter than the end of the span.
+    fn span(&self, span: Span) -> &str;
+
+    /// Gets the part of the string covered by `span`, with `start` and `end` corresponding to char indexes. This is
+    /// the same as [`Span::try_apply`].
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if the string is shorter than the end of the span.
+    fn try_span(&self, span: Span) -> Result<&str, SpanError>;
+
+    /// Gets the part of the string covered by `span`, with `start` and `end` corresponding to byte indexes. This is
+    /// the same as [`Span::apply_bytes`].
+    ///
+    /// # Panics
+    /// Panics if the string is shorter than the end of the span.
+    fn span_bytes(&self, span: Span) -> &str;
+
+    /// Gets the part of the string covered by `span`, with `start` and `end` corresponding to byte indexes. This is
+    /// the same as [`Span::try_apply_bytes`].
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if the string is shorter than the end of the span, and
+    /// [`SpanError::NotCharBoundary`] if `start` or `end` isn't on a char boundary.
+    fn try_span_bytes(&self, span: Span) -> Result<&str, SpanError>;
+}
+
+impl StrSpanExt for str {
+    #[inline(always)]
+    fn span(&self, span: Span) -> &str {
+        span.apply(self)
+    }
+
+    #[inline(always)]
+    fn try_span(&self, span: Span) -> Result<&str, SpanError> {
+        span.try_apply(self)
+    }
+
+    #[inline(always)]
+    fn span_bytes(&self, span: Span) -> &str {
+        span.apply_bytes(self)
+    }
+
+    #[inline(always)]
+    fn try_span_bytes(&self, span: Span) -> Result<&str, SpanError> {
+        span.try_apply_bytes(self)
+    }
+}
@@ -1,239 +1,751 @@
 #![no_std]
 
-use core::{cmp::Ordering, ops::Range};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod ext;
+#[cfg(feature = "alloc")]
+mod map;
+#[cfg(feature = "alloc")]
+mod set;
+mod span_int;
+mod spanned;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+pub use ext::StrSpanExt;
+#[cfg(feature = "alloc")]
+pub use map::SpanMap;
+#[cfg(feature = "alloc")]
+pub use set::SpanSet;
+pub use span_int::SpanInt;
+pub use spanned::{Spanned, SpannedSource};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{cmp::Ordering, fmt, num::ParseIntError};
+
+// If more than one `span-value-*` feature is enabled, the widest type wins, and if none are, `usize` is used.
 
-#[cfg(feature = "span-value-usize")]
-/// Type of span values
-pub type SpanValue = usize;
 #[cfg(feature = "span-value-u128")]
 /// Type of span values
 pub type SpanValue = u128;
-#[cfg(feature = "span-value-u64")]
+#[cfg(all(feature = "span-value-u64", not(feature = "span-value-u128")))]
 /// Type of span values
 pub type SpanValue = u64;
-#[cfg(feature = "span-value-u32")]
+#[cfg(not(any(
+    feature = "span-value-u128",
+    feature = "span-value-u64",
+    all(
+        not(feature = "span-value-usize"),
+        any(
+            feature = "span-value-u32",
+            feature = "span-value-u16",
+            feature = "span-value-u8"
+        )
+    )
+)))]
+/// Type of span values
+pub type SpanValue = usize;
+#[cfg(all(
+    feature = "span-value-u32",
+    not(any(
+        feature = "span-value-u128",
+        feature = "span-value-u64",
+        feature = "span-value-usize"
+    ))
+))]
 /// Type of span values
 pub type SpanValue = u32;
-#[cfg(feature = "span-value-u16")]
+#[cfg(all(
+    feature = "span-value-u16",
+    not(any(
+        feature = "span-value-u128",
+        feature = "span-value-u64",
+        feature = "span-value-usize",
+        feature = "span-value-u32"
+    ))
+))]
 /// Type of span values
 pub type SpanValue = u16;
-#[cfg(feature = "span-value-u8")]
+#[cfg(all(
+    feature = "span-value-u8",
+    not(any(
+        feature = "span-value-u128",
+        feature = "span-value-u64",
+        feature = "span-value-usize",
+        feature = "span-value-u32",
+        feature = "span-value-u16"
+    ))
+))]
 /// Type of span values
 pub type SpanValue = u8;
 
-/// The `Span` type represents an area of a file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub struct Span {
+/// Signed counterpart of [`SpanValue`], used to move spans in either direction
+pub type SpanDelta = <SpanValue as SpanInt>::Delta;
+
+/// The `Span` type represents an area of a file, with the `SpanValue` type picked by the `span-value-*` features.
+///
+/// This is the [`GenericSpan`] of `SpanValue`, and all of its methods are documented there.
+pub type Span = GenericSpan<SpanValue>;
+
+/// The archived form of a [`Span`], made by `rkyv`.
+#[cfg(feature = "rkyv")]
+pub type ArchivedSpan = ArchivedGenericSpan<SpanValue>;
+
+/// An area of a file, with `start` and `end` values of the integer type `T`, which is one of the [`SpanInt`] types.
+///
+/// Every integer type has its own copy of the methods, so they stay `const fn`. [`Span`] uses the `SpanValue` type
+/// picked by the `span-value-*` features, but a library that needs a particular width, no matter what other crates
+/// in the dependency tree pick, can use a `GenericSpan` of that width instead. Since every width has a method with
+/// the same name, calling an associated function on `GenericSpan` needs the width written out, like
+/// `GenericSpan::<u32>::new_from(1, 3)`.
+///
+/// `GenericSpan`'s `Hash` impl hashes `start` and then `end`, and this is guaranteed not to change between
+/// versions, so hashes of equal spans only differ if the `Hasher` or the integer type does.
+///
+/// The `Default` span is the empty span at `0`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(rename = "Span"))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(
+        bytecheck(verify),
+        archive_bounds(T::Archived: fmt::Debug + Eq),
+        derive(Debug, PartialEq, Eq)
+    )
+)]
+#[repr(C)]
+pub struct GenericSpan<T> {
     /// The start of the `Span` (Inclusive)
-    pub start: SpanValue,
+    pub start: T,
     /// The end of the `Span` (Exclusive)
-    pub end: SpanValue,
+    pub end: T,
 }
 
-impl Span {
-    /// Creates a new `Span`. This span will start and end at the 0th character, making it have a length of zero.
-    #[inline(always)]
-    pub fn new() -> Self {
-        Self::new_from(0, 0)
+impl<T: fmt::Debug> fmt::Debug for GenericSpan<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Span")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
     }
+}
 
-    /// Creates a new `Span` from a pair of start and end indexes.
-    ///
-    /// # Panics
-    /// Panics if start is greater than end, since spans can't have a negative length.
-    #[inline(always)]
-    pub fn new_from(start: SpanValue, end: SpanValue) -> Self {
-        assert!(end >= start, "cannot create negative-size span");
+/// A side of a [`Span`], for growing or shrinking it in a direction chosen at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The front of the span, where its end value is.
+    Front,
+    /// The back of the span, where its start value is.
+    Back,
+}
 
-        Span { start, end }
-    }
+/// Implements everything that depends on the integer type of a span, once for each [`SpanInt`] type. Each module
+/// gives `span_impl.rs` its own `SpanValue`, `SpanDelta`, `Span` and `SpanError` aliases, so the code there reads the
+/// same for every width.
+macro_rules! span_impls {
+    ($($module:ident: $value:ty;)*) => {
+        $(
+            mod $module {
+                use super::*;
 
-    /// Grows the span from the front. This moves the end value up by `amount`.
-    #[inline(always)]
-    pub fn grow_front(&mut self, amount: SpanValue) {
-        self.end += amount;
-    }
+                type SpanValue = $value;
+                type SpanDelta = <SpanValue as SpanInt>::Delta;
+                type Span = GenericSpan<SpanValue>;
+                type SpanError = super::SpanError<SpanValue>;
+                #[cfg(feature = "rkyv")]
+                type ArchivedSpan = ArchivedGenericSpan<SpanValue>;
 
-    /// Returns a span that is grown from the front. This moves the end value up by `amount`.
-    #[inline(always)]
-    pub fn with_grow_front(&self, amount: SpanValue) -> Self {
-        let mut new = *self;
-        new.end += amount;
-        new
-    }
+                include!("span_impl.rs");
+            }
+        )*
+    };
+}
 
-    /// Grows the span from the back. This moves the start value back by `amount`.
-    ///
-    /// # Panics
-    /// Panics if the start of the span is less than `amount`, since spans can't have a negative start value,
-    #[inline(always)]
-    pub fn grow_back(&mut self, amount: SpanValue) {
-        assert!(
-            self.start >= amount,
-            "cannot create a span with a negative start value"
-        );
-        self.start -= amount;
-    }
+span_impls! {
+    span_u8: u8;
+    span_u16: u16;
+    span_u32: u32;
+    span_u64: u64;
+    span_u128: u128;
+    span_usize: usize;
+}
 
-    /// Returns a span that is grown from the back. This moves the start value back by `amount`.
-    ///
-    /// # Panics
-    /// Panics if the start of the span is less than `amount`, since spans can't have a negative start value,
-    #[inline(always)]
-    pub fn with_grow_back(&self, amount: SpanValue) -> Self {
-        assert!(
-            self.start >= amount,
-            "cannot create a span with a negative start value"
-        );
-        let mut new = *self;
-        new.start -= amount;
-        new
-    }
+/// Merges overlapping and adjacent spans in `spans` into a minimal set of disjoint spans, sorted by their start.
+///
+/// Adjacent spans are merged too, so `0..5` and `5..10` become `0..10`. Empty spans are dropped if they lie inside
+/// or on the edge of another span, and kept otherwise.
+#[cfg(feature = "alloc")]
+pub fn coalesce(spans: &[Span]) -> Vec<Span> {
+    let mut sorted = spans.to_vec();
+    sorted.sort_unstable_by_key(|span| (span.start, span.end));
 
-    /// Shrinks the span from the back. This moves the start value up by `amount`.
-    ///
-    /// # Panics
-    /// Panics if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
-    #[inline(always)]
-    pub fn shrink_back(&mut self, amount: SpanValue) {
-        assert!(self.len() >= amount, "cannot create negative-size span");
-        self.start += amount;
+    let mut merged: Vec<Span> = Vec::with_capacity(sorted.len());
+    for span in sorted {
+        match merged.last_mut() {
+            Some(last) if span.start <= last.end => last.end = last.end.max(span.end),
+            _ => merged.push(span),
+        }
     }
+    merged
+}
 
-    /// Returns a span that is shrunk from the back. This moves the start value up by `amount`.
-    ///
-    /// # Panics
-    /// Panics if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
-    #[inline(always)]
-    pub fn with_shrink_back(&self, amount: SpanValue) -> Self {
-        assert!(self.len() >= amount, "cannot create negative-size span");
-        let mut new = *self;
-        new.start += amount;
-        new
-    }
+/// Sums the lengths of all of the spans in `spans`, stopping at `SpanValue::MAX` instead of overflowing.
+///
+/// Overlapping areas are counted once for every span that covers them. To count every position only once,
+/// `coalesce` the spans first, which needs the `alloc` feature.
+pub fn total_len<I: IntoIterator<Item = Span>>(spans: I) -> SpanValue {
+    spans
+        .into_iter()
+        .fold(0, |total, span| total.saturating_add(span.len()))
+}
 
-    /// Shrinks the span from the front. This moves the end value back by `amount`.
-    ///
-    /// # Panics
-    /// This method will panic if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
-    #[inline(always)]
-    pub fn shrink_front(&mut self, amount: SpanValue) {
-        assert!(self.len() >= amount, "cannot create negative-size span");
-        self.end -= amount;
+/// An error returned by the fallible `try_*` operations on `Span`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SpanError<T = SpanValue> {
+    /// The end of the span would be before its start.
+    NegativeSize {
+        /// The start of the span
+        start: T,
+        /// The end of the span
+        end: T,
+    },
+    /// A value would be greater than the largest value of the span's integer type.
+    Overflow,
+    /// A value would be less than `0`.
+    Underflow,
+    /// The span ends after the end of the string or slice it was used with.
+    OutOfBounds,
+    /// The span was used with byte indexes into a string, and its start or end isn't on a char boundary.
+    NotCharBoundary,
+}
+
+impl<T: fmt::Display> fmt::Display for SpanError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NegativeSize { start, end } => {
+                write!(f, "cannot create negative-size span {start}..{end}")
+            }
+            Self::Overflow => write!(f, "span value would be past the end of its integer type"),
+            Self::Underflow => write!(f, "span value would be less than 0"),
+            Self::OutOfBounds => write!(f, "span ends after the end of the source"),
+            Self::NotCharBoundary => write!(f, "span doesn't lie on char boundaries of the string"),
+        }
     }
+}
 
-    /// Returns a span shrunk from the front. This moves the end value back by `amount`.
-    ///
-    /// # Panics
-    /// This method will panic if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
-    #[inline(always)]
-    pub fn with_shrink_front(&self, amount: SpanValue) -> Self {
-        assert!(self.len() >= amount, "cannot create negative-size span");
-        let mut new = *self;
-        new.end -= amount;
-        new
+impl<T: fmt::Debug + fmt::Display> core::error::Error for SpanError<T> {}
+
+/// An error returned when parsing a `Span` from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSpanError {
+    /// The string doesn't contain the `..` separator.
+    MissingSeparator,
+    /// The start or end of the span isn't a valid integer.
+    InvalidInteger(ParseIntError),
+    /// The end of the span is before its start.
+    NegativeSize,
+}
+
+impl fmt::Display for ParseSpanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSeparator => write!(f, "missing `..` separator in span"),
+            Self::InvalidInteger(err) => write!(f, "invalid integer in span: {err}"),
+            Self::NegativeSize => write!(f, "cannot create negative-size span"),
+        }
     }
+}
 
-    /// Checks if a `Span`'s size is `0`. Returns `true` if `0`, and false if anything else.
-    #[inline(always)]
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+impl core::error::Error for ParseSpanError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::InvalidInteger(err) => Some(err),
+            _ => None,
+        }
     }
+}
 
-    /// Gets the length of a `Span`.
-    #[inline(always)]
-    pub fn len(&self) -> SpanValue {
-        self.end - self.start
+fn dual_order(x: Ordering, y: Ordering) -> Option<Ordering> {
+    match (x, y) {
+        (x, y) if x == y => Some(x),
+        (Ordering::Greater, Ordering::Less) | (Ordering::Less, Ordering::Greater) => None,
+        (x, Ordering::Equal) => Some(x),
+        (Ordering::Equal, x) => Some(x),
+        _ => unreachable!(),
     }
+}
 
-    /// Resets `self` by changing the start to be the end, plus 1, and changing the end to be the start.
-    /// The function also returns the old span.
-    #[inline(always)]
-    pub fn reset(&mut self) -> Self {
-        let span = *self;
-        self.start = self.end;
-        span
-    }
-
-    /// Applies the span to `string`, with `start` and `end` corresponding to char indexes.
-    ///
-    /// # Panics
-    /// Panics if `string` is shorter than the end of the span.
-    #[allow(clippy::unnecessary_cast)]
-    pub fn apply<'a>(&self, string: &'a str) -> &'a str {
-        let mut chars = string.char_indices();
-
-        let start = chars
-            .nth(self.start as usize)
-            .expect("string is too short to have the span applied")
-            .0;
-        let end = chars
-            .nth(self.len() as usize)
-            .expect("string is too short to have the span applied")
-            .0;
-        &string[start..end]
-    }
-
-    /// Applies the span to `string`, with `start` and `end` corresponding to byte indexes.
-    ///
-    /// # Panics
-    /// Panics if `string` is shorter than the end of the span.
-    #[allow(clippy::unnecessary_cast)]
-    pub fn apply_bytes<'a>(&self, string: &'a str) -> &'a str {
-        assert!(
-            string.len() >= self.end as usize,
-            "string is too short to have the span applied"
-        );
-        &string[(self.start as usize)..(self.end as usize)]
-    }
-}
-
-impl From<Span> for Range<SpanValue> {
+/// A `Span` with a total order, comparing by `start` and then by `end`.
+///
+/// `Span` itself only implements [`PartialOrd`]: one span is less than another if neither of its ends is after the
+/// other span's, so spans that cross over each other, like `0..10` and `2..5`, can't be compared. Wrapping spans in
+/// `OrderedSpan` lets them be sorted or used as keys in ordered collections like `BTreeMap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OrderedSpan<T = SpanValue>(pub GenericSpan<T>);
+
+impl<T: Ord> Ord for OrderedSpan<T> {
     #[inline(always)]
-    fn from(val: Span) -> Self {
-        val.start..val.end
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.0.start, &self.0.end).cmp(&(&other.0.start, &other.0.end))
     }
 }
 
-impl From<Range<SpanValue>> for Span {
+impl<T: Ord> PartialOrd for OrderedSpan<T> {
     #[inline(always)]
-    fn from(value: Range<SpanValue>) -> Self {
-        Self::new_from(value.start, value.end)
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl PartialOrd for Span {
+impl<T> From<GenericSpan<T>> for OrderedSpan<T> {
     #[inline(always)]
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        dual_order(self.start.cmp(&other.start), self.end.cmp(&other.end))
+    fn from(value: GenericSpan<T>) -> Self {
+        Self(value)
     }
 }
 
-fn dual_order(x: Ordering, y: Ordering) -> Option<Ordering> {
-    match (x, y) {
-        (x, y) if x == y => Some(x),
-        (Ordering::Greater, Ordering::Less) | (Ordering::Less, Ordering::Greater) => None,
-        (x, Ordering::Equal) => Some(x),
-        (Ordering::Equal, x) => Some(x),
-        _ => unreachable!(),
+impl<T> From<OrderedSpan<T>> for GenericSpan<T> {
+    #[inline(always)]
+    fn from(val: OrderedSpan<T>) -> Self {
+        val.0
     }
 }
 
 #[cfg(feature = "ariadne")]
-impl ariadne::Span for Span {
-    type SourceId = ();
+impl<S: PartialEq + Clone> ariadne::Span for SpannedSource<S> {
+    type SourceId = S;
 
     fn source(&self) -> &Self::SourceId {
-        &()
+        &self.source
     }
 
-    #[allow(clippy::unnecessary_cast)]
     fn start(&self) -> usize {
-        self.start as usize
+        self.span
+            .start
+            .to_usize()
+            .expect("span doesn't fit in usize")
     }
 
-    #[allow(clippy::unnecessary_cast)]
     fn end(&self) -> usize {
-        self.end as usize
+        self.span.end.to_usize().expect("span doesn't fit in usize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::hash::{Hash, Hasher};
+    use std::hash::DefaultHasher;
+
+    #[test]
+    fn apply_ascii() {
+        assert_eq!(Span { start: 1, end: 3 }.apply("abcd"), "bc");
+        assert_eq!(Span { start: 0, end: 0 }.apply("abcd"), "");
+        assert_eq!(Span { start: 2, end: 2 }.apply("abcd"), "");
+    }
+
+    #[test]
+    fn apply_multibyte() {
+        assert_eq!(Span { start: 1, end: 3 }.apply("aé€d"), "é€");
+        assert_eq!(Span { start: 0, end: 1 }.apply("€d"), "€");
+        assert_eq!(Span { start: 1, end: 2 }.apply("🦀é"), "é");
+    }
+
+    #[test]
+    fn apply_out_of_bounds() {
+        assert_eq!(
+            Span { start: 1, end: 5 }.try_apply("aé€d"),
+            Err(SpanError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "string is too short to have the span applied")]
+    fn apply_panics_out_of_bounds() {
+        Span { start: 1, end: 5 }.apply("aé€d");
+    }
+
+    #[test]
+    fn apply_to_end_of_string() {
+        assert_eq!(Span { start: 2, end: 4 }.apply("abcd"), "cd");
+        assert_eq!(Span { start: 2, end: 4 }.apply("aé€d"), "€d");
+        assert_eq!(Span { start: 0, end: 4 }.apply("aé€d"), "aé€d");
+        assert_eq!(Span { start: 4, end: 4 }.apply("aé€d"), "");
+        assert_eq!(Span { start: 0, end: 0 }.apply(""), "");
+    }
+
+    #[test]
+    fn apply_bytes_to_end_of_string() {
+        assert_eq!(Span { start: 2, end: 4 }.apply_bytes("abcd"), "cd");
+        assert_eq!(Span { start: 3, end: 7 }.apply_bytes("aé€d"), "€d");
+        assert_eq!(Span { start: 0, end: 7 }.apply_bytes("aé€d"), "aé€d");
+        assert_eq!(Span { start: 7, end: 7 }.apply_bytes("aé€d"), "");
+        assert_eq!(Span { start: 0, end: 0 }.apply_bytes(""), "");
+    }
+
+    #[test]
+    fn apply_bytes_out_of_bounds() {
+        assert_eq!(
+            Span { start: 3, end: 8 }.try_apply_bytes("aé€d"),
+            Err(SpanError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot create a span with an end value past SpanValue::MAX")]
+    fn grow_front_panics_past_max() {
+        GenericSpan::<u8>::new_from(250, 255).grow_front(10);
+    }
+
+    #[test]
+    fn checked_grow_front_past_max() {
+        let span = GenericSpan::<u8>::new_from(250, 250);
+        assert_eq!(span.checked_grow_front(6), None);
+        assert_eq!(
+            span.checked_grow_front(5),
+            Some(GenericSpan::<u8>::new_from(250, 255))
+        );
+    }
+
+    #[test]
+    fn advance_returns_old_span() {
+        let mut span = Span { start: 2, end: 5 };
+        assert_eq!(span.advance(), Span { start: 2, end: 5 });
+        assert_eq!(span, Span { start: 5, end: 5 });
+        assert_eq!(span.advance(), Span { start: 5, end: 5 });
+        assert_eq!(span, Span { start: 5, end: 5 });
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn reset_matches_advance() {
+        let mut span = Span { start: 2, end: 5 };
+        assert_eq!(span.reset(), Span { start: 2, end: 5 });
+        assert_eq!(span, Span { start: 5, end: 5 });
+    }
+
+    fn hash_of(hash: impl FnOnce(&mut DefaultHasher)) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_spans_hash_equally() {
+        assert_eq!(
+            hash_of(|state| Span { start: 2, end: 5 }.hash(state)),
+            hash_of(|state| Span::new_from(2, 5).hash(state))
+        );
+    }
+
+    #[test]
+    fn hash_start_ignores_end() {
+        assert_eq!(
+            hash_of(|state| Span { start: 2, end: 5 }.hash_start(state)),
+            hash_of(|state| Span { start: 2, end: 9 }.hash_start(state))
+        );
+        assert_eq!(
+            hash_of(|state| Span { start: 2, end: 5 }.hash_start(state)),
+            hash_of(|state| (2 as SpanValue).hash(state))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot create a span with an end value past SpanValue::MAX")]
+    fn expand_to_include_panics_past_max() {
+        GenericSpan::<u8>::new_from(250, 252).expand_to_include(u8::MAX);
+    }
+
+    #[test]
+    fn relative_to_rebases() {
+        let base = Span { start: 10, end: 20 };
+        assert_eq!(
+            Span { start: 1, end: 3 }.relative_to(&base),
+            Span { start: 11, end: 13 }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "span doesn't fit inside its base")]
+    fn relative_to_panics_past_max() {
+        GenericSpan::<u8>::new_from(10, 20).relative_to(&GenericSpan::<u8>::new_from(250, 255));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "span has a negative size")]
+    fn expand_to_include_checks_well_formed() {
+        Span { start: 5, end: 2 }.expand_to_include(3);
+    }
+
+    #[test]
+    fn line_col_crlf() {
+        let source = "ab\r\ncd\nef";
+        assert_eq!(Span { start: 0, end: 0 }.line_col(source), (1, 1));
+        assert_eq!(Span { start: 3, end: 3 }.line_col(source), (1, 3));
+        assert_eq!(Span { start: 4, end: 4 }.line_col(source), (2, 1));
+        assert_eq!(Span { start: 5, end: 6 }.line_col(source), (2, 2));
+        assert_eq!(Span { start: 7, end: 7 }.line_col(source), (3, 1));
+    }
+
+    #[test]
+    fn line_col_past_end() {
+        assert_eq!(Span { start: 20, end: 20 }.line_col("ab\r\ncd"), (2, 3));
+        assert_eq!(Span { start: 1, end: 1 }.line_col(""), (1, 1));
+    }
+
+    #[test]
+    fn apply_slice_to_end_of_slice() {
+        assert_eq!(Span { start: 1, end: 3 }.apply_slice(b"abc"), b"bc");
+        assert_eq!(Span { start: 3, end: 3 }.apply_slice(b"abc"), b"");
+    }
+
+    #[test]
+    #[should_panic(expected = "slice is too short to have the span applied")]
+    fn apply_slice_panics_past_usize() {
+        GenericSpan::<u128> {
+            start: 0,
+            end: 1 << 64,
+        }
+        .apply_slice(b"abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "span ends after the end of the source")]
+    fn apply_bytes_panics_past_usize() {
+        GenericSpan::<u128> {
+            start: 0,
+            end: 1 << 64,
+        }
+        .apply_bytes("abc");
+    }
+
+    #[test]
+    fn line_col_past_usize() {
+        let span = GenericSpan::<u128> {
+            start: 1 << 64,
+            end: 1 << 64,
+        };
+        assert_eq!(span.line_col("ab\ncd"), (2, 3));
+    }
+
+    #[test]
+    fn difference_splits_overlapping_span() {
+        let span = Span { start: 0, end: 10 };
+        assert_eq!(
+            span.difference(&Span { start: 3, end: 5 }),
+            (
+                Some(Span { start: 0, end: 3 }),
+                Some(Span { start: 5, end: 10 })
+            )
+        );
+        assert_eq!(span.difference(&Span { start: 0, end: 10 }), (None, None));
+    }
+
+    #[test]
+    fn difference_with_disjoint_span() {
+        let span = Span { start: 5, end: 10 };
+        assert_eq!(
+            span.difference(&Span { start: 0, end: 5 }),
+            (None, Some(span))
+        );
+        assert_eq!(
+            span.difference(&Span { start: 10, end: 12 }),
+            (Some(span), None)
+        );
+        assert_eq!(
+            span.difference(&Span { start: 7, end: 7 }),
+            (Some(span), None)
+        );
+        assert_eq!(
+            span.difference(&Span { start: 5, end: 5 }),
+            (None, Some(span))
+        );
+        assert_eq!(
+            Span { start: 3, end: 3 }.difference(&Span { start: 0, end: 1 }),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn contains_range_inclusive_end() {
+        let span = Span { start: 0, end: 5 };
+        assert!(span.contains_range_inclusive(&(0..=4)));
+        assert!(span.contains_range_inclusive(&(2..=2)));
+        assert!(!span.contains_range_inclusive(&(0..=5)));
+        assert!(span.contains_range(&(0..5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "span ends after the end of the source")]
+    fn to_char_span_panics_out_of_bounds() {
+        Span { start: 0, end: 10 }.to_char_span("abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "span doesn't lie on char boundaries of the string")]
+    fn to_char_span_panics_off_char_boundary() {
+        Span { start: 0, end: 1 }.to_char_span("é");
+    }
+
+    #[test]
+    #[should_panic(expected = "span ends after the end of the source")]
+    fn apply_bytes_with_context_panics_out_of_bounds() {
+        Span { start: 0, end: 10 }.apply_bytes_with_context("abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "span value would be past the end of its integer type")]
+    fn to_byte_span_panics_on_overflow() {
+        GenericSpan::<u8>::new_from(0, 200).to_byte_span(&"é".repeat(200));
+    }
+
+    #[cfg(feature = "alloc")]
+    fn set_of(spans: &[Span]) -> SpanSet {
+        spans.iter().copied().collect()
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_set_merges_adjacent_span() {
+        let mut set = set_of(&[Span { start: 0, end: 5 }]);
+        set.insert(Span { start: 5, end: 8 });
+        assert_eq!(set.as_slice(), &[Span { start: 0, end: 8 }]);
+
+        set.insert(Span { start: 10, end: 12 });
+        set.insert(Span { start: 9, end: 10 });
+        assert_eq!(
+            set.as_slice(),
+            &[Span { start: 0, end: 8 }, Span { start: 9, end: 12 }]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_set_merges_across_several_spans() {
+        let mut set = set_of(&[
+            Span { start: 0, end: 2 },
+            Span { start: 4, end: 6 },
+            Span { start: 8, end: 10 },
+            Span { start: 20, end: 25 },
+        ]);
+        set.insert(Span { start: 1, end: 9 });
+        assert_eq!(
+            set.as_slice(),
+            &[Span { start: 0, end: 10 }, Span { start: 20, end: 25 }]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_set_remove_splits_span() {
+        let mut set = set_of(&[Span { start: 0, end: 10 }]);
+        set.remove(Span { start: 3, end: 5 });
+        assert_eq!(
+            set.as_slice(),
+            &[Span { start: 0, end: 3 }, Span { start: 5, end: 10 }]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_set_remove_touching_nothing() {
+        let mut set = set_of(&[Span { start: 0, end: 5 }, Span { start: 10, end: 15 }]);
+        set.remove(Span { start: 5, end: 10 });
+        assert_eq!(
+            set.as_slice(),
+            &[Span { start: 0, end: 5 }, Span { start: 10, end: 15 }]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_set_contains_point_at_end() {
+        let set = set_of(&[Span { start: 0, end: 5 }, Span { start: 10, end: 15 }]);
+        assert!(set.contains_point(4));
+        assert!(!set.contains_point(5));
+        assert!(set.contains_point(10));
+        assert!(!set.contains_point(15));
+    }
+
+    #[cfg(feature = "alloc")]
+    fn entries_of<V: Copy>(map: &SpanMap<V>) -> Vec<(Span, V)> {
+        map.iter().map(|(span, value)| (span, *value)).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_map_insert_inside_entry_splits_it() {
+        let mut map = SpanMap::new();
+        map.insert(Span { start: 0, end: 10 }, 'a');
+        map.insert(Span { start: 3, end: 5 }, 'b');
+        assert_eq!(
+            entries_of(&map),
+            [
+                (Span { start: 0, end: 3 }, 'a'),
+                (Span { start: 3, end: 5 }, 'b'),
+                (Span { start: 5, end: 10 }, 'a'),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_map_insert_partial_overlap() {
+        let mut map = SpanMap::new();
+        map.insert(Span { start: 0, end: 5 }, 'a');
+        map.insert(Span { start: 10, end: 15 }, 'b');
+        map.insert(Span { start: 3, end: 12 }, 'c');
+        assert_eq!(
+            entries_of(&map),
+            [
+                (Span { start: 0, end: 3 }, 'a'),
+                (Span { start: 3, end: 12 }, 'c'),
+                (Span { start: 12, end: 15 }, 'b'),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn span_map_get_at_boundaries() {
+        let mut map = SpanMap::new();
+        map.insert(Span { start: 0, end: 5 }, 'a');
+        map.insert(Span { start: 5, end: 8 }, 'b');
+        map.insert(Span { start: 10, end: 12 }, 'c');
+        assert_eq!(map.get(0), Some(&'a'));
+        assert_eq!(map.get(4), Some(&'a'));
+        assert_eq!(map.get(5), Some(&'b'));
+        assert_eq!(map.get(8), None);
+        assert_eq!(map.get(10), Some(&'c'));
+        assert_eq!(map.get(12), None);
+    }
+
+    #[test]
+    fn compare_with_point() {
+        let span = Span { start: 2, end: 5 };
+        assert!(span < 5);
+        assert!(span > 1);
+        assert!(span == 2);
+        assert!(span == 4);
+        assert!(span != 5);
+        assert_eq!(span.partial_cmp(&3), Some(Ordering::Equal));
+        assert!(Span { start: 3, end: 3 } < 3);
+        assert!(Span { start: 3, end: 3 } != 3);
+    }
+
+    #[test]
+    fn try_apply_negative_size() {
+        let span = Span { start: 3, end: 1 };
+        let err = SpanError::NegativeSize { start: 3, end: 1 };
+        assert_eq!(span.try_apply("abcd"), Err(err));
+        assert_eq!(span.try_apply_bytes("abcd"), Err(err));
+        assert_eq!(span.try_apply_slice(b"abcd"), Err(err));
     }
 }
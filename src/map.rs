@@ -0,0 +1,99 @@
+use alloc::vec::Vec;
+
+use crate::{Span, SpanValue};
+
+/// A map from positions to values, stored as a sorted list of disjoint spans that each have a value.
+///
+/// Inserting a span overwrites the values of every position it covers. Existing spans that only partly overlap
+/// the inserted span keep their value on the positions outside of it, which can split an existing span in two.
+/// Neighbouring spans are never merged, even if their values are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpanMap<V> {
+    entries: Vec<(Span, V)>,
+}
+
+impl<V> SpanMap<V> {
+    /// Creates a new, empty `SpanMap`.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Gets the value of the span covering `point`, or `None` if no span covers it.
+    pub fn get(&self, point: SpanValue) -> Option<&V> {
+        let index = self.entries.partition_point(|(span, _)| span.end <= point);
+        self.entries
+            .get(index)
+            .filter(|(span, _)| span.contains_point(point))
+            .map(|(_, value)| value)
+    }
+
+    /// Gets the number of disjoint spans in the map.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if the map has no spans.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the spans in the map and their values, sorted by the start of the spans.
+    pub fn iter(&self) -> impl Iterator<Item = (Span, &V)> {
+        self.entries.iter().map(|(span, value)| (*span, value))
+    }
+}
+
+impl<V: Clone> SpanMap<V> {
+    /// Sets the value of every position covered by `span` to `value`.
+    ///
+    /// Spans already in the map lose the positions `span` covers. If a span only partly overlaps `span`, the part
+    /// outside of it keeps its old value, and if `span` lies strictly inside an existing span, that span is split
+    /// into a part before and a part after `span`, both keeping the old value. Empty spans cover no positions, so
+    /// inserting one does nothing.
+    pub fn insert(&mut self, span: Span, value: V) {
+        if span.is_empty() {
+            return;
+        }
+
+        let first = self
+            .entries
+            .partition_point(|(other, _)| other.end <= span.start);
+        let last = self
+            .entries
+            .partition_point(|(other, _)| other.start < span.end);
+
+        let (before, after) = if first < last {
+            let (first_span, first_value) = &self.entries[first];
+            let (last_span, last_value) = &self.entries[last - 1];
+            (
+                first_span
+                    .difference(&span)
+                    .0
+                    .map(|before| (before, first_value.clone())),
+                last_span
+                    .difference(&span)
+                    .1
+                    .map(|after| (after, last_value.clone())),
+            )
+        } else {
+            (None, None)
+        };
+
+        self.entries.splice(
+            first..last,
+            before.into_iter().chain(Some((span, value))).chain(after),
+        );
+    }
+}
+
+impl<V> Default for SpanMap<V> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
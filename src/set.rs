@@ -0,0 +1,116 @@
+use alloc::vec::Vec;
+use core::{iter, slice};
+
+use crate::{Span, SpanValue};
+
+/// A set of positions, stored as a sorted list of disjoint spans.
+///
+/// Inserted spans are merged with any spans they overlap or are adjacent to, so the set always holds the fewest
+/// spans that cover its positions. Lookups are a binary search, and inserting or removing shifts the spans after
+/// the affected area.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SpanSet {
+    spans: Vec<Span>,
+}
+
+impl SpanSet {
+    /// Creates a new, empty `SpanSet`.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { spans: Vec::new() }
+    }
+
+    /// Adds the positions covered by `span` to the set, merging it with any spans it overlaps or is adjacent to.
+    /// Empty spans cover no positions, so inserting one does nothing.
+    pub fn insert(&mut self, span: Span) {
+        if span.is_empty() {
+            return;
+        }
+
+        let first = self.spans.partition_point(|other| other.end < span.start);
+        let last = self.spans.partition_point(|other| other.start <= span.end);
+        if first == last {
+            self.spans.insert(first, span);
+            return;
+        }
+
+        let merged = span.union(&self.spans[first]).union(&self.spans[last - 1]);
+        self.spans.splice(first..last, iter::once(merged));
+    }
+
+    /// Removes the positions covered by `span` from the set, splitting any span that only partly overlaps it.
+    pub fn remove(&mut self, span: Span) {
+        if span.is_empty() {
+            return;
+        }
+
+        let first = self.spans.partition_point(|other| other.end <= span.start);
+        let last = self.spans.partition_point(|other| other.start < span.end);
+        if first == last {
+            return;
+        }
+
+        let (before, _) = self.spans[first].difference(&span);
+        let (_, after) = self.spans[last - 1].difference(&span);
+        self.spans
+            .splice(first..last, before.into_iter().chain(after));
+    }
+
+    /// Checks if `point` is covered by one of the spans in the set.
+    pub fn contains_point(&self, point: SpanValue) -> bool {
+        let index = self.spans.partition_point(|span| span.end <= point);
+        self.spans
+            .get(index)
+            .is_some_and(|span| span.contains_point(point))
+    }
+
+    /// Gets the number of disjoint spans in the set.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Checks if the set covers no positions.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns an iterator over the disjoint spans in the set, sorted by their start.
+    #[inline(always)]
+    pub fn iter(&self) -> slice::Iter<'_, Span> {
+        self.spans.iter()
+    }
+
+    /// Gets the disjoint spans in the set, sorted by their start.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+impl<'a> IntoIterator for &'a SpanSet {
+    type Item = &'a Span;
+    type IntoIter = slice::Iter<'a, Span>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Extend<Span> for SpanSet {
+    fn extend<I: IntoIterator<Item = Span>>(&mut self, iter: I) {
+        for span in iter {
+            self.insert(span);
+        }
+    }
+}
+
+impl FromIterator<Span> for SpanSet {
+    fn from_iter<I: IntoIterator<Item = Span>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
@@ -0,0 +1,1795 @@
+// Everything that depends on the integer type of a span. This file is included once for each `SpanInt` type by
+// `span_impls!` in `lib.rs`, which defines the `SpanValue`, `SpanDelta`, `Span` and `SpanError` aliases it uses.
+
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    num::NonZero,
+    ops::{
+        Add, AddAssign, Bound, Range, RangeBounds, RangeFrom, RangeInclusive, RangeTo, Sub,
+        SubAssign,
+    },
+    str::FromStr,
+};
+
+impl Span {
+    /// The empty span at `0`, which is also the `Default` span.
+    pub const ZERO: Span = Span { start: 0, end: 0 };
+
+    /// Creates a new `Span`. This span will start and end at the 0th character, making it have a length of zero.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self::new_from(0, 0)
+    }
+
+    /// Creates a new `Span` between two points given in either order, like the anchor and head of a selection. Unlike
+    /// [`new_from`](Span::new_from), this never panics.
+    #[inline(always)]
+    pub const fn covering(a: SpanValue, b: SpanValue) -> Self {
+        if a <= b {
+            Span { start: a, end: b }
+        } else {
+            Span { start: b, end: a }
+        }
+    }
+
+    /// Creates an empty `Span` at `pos`, starting and ending there.
+    #[inline(always)]
+    pub const fn empty_at(pos: SpanValue) -> Self {
+        Span {
+            start: pos,
+            end: pos,
+        }
+    }
+
+    /// Creates a new `Span` from a pair of start and end indexes.
+    ///
+    /// # Panics
+    /// Panics if start is greater than end, since spans can't have a negative length.
+    #[inline(always)]
+    pub const fn new_from(start: SpanValue, end: SpanValue) -> Self {
+        assert!(end >= start, "cannot create negative-size span");
+
+        Span { start, end }
+    }
+
+    /// Creates a new `Span` from a pair of start and end indexes.
+    ///
+    /// Returns [`SpanError::NegativeSize`] if start is greater than end, since spans can't have a negative length.
+    #[inline(always)]
+    pub const fn try_new_from(start: SpanValue, end: SpanValue) -> Result<Self, SpanError> {
+        if end < start {
+            return Err(SpanError::NegativeSize { start, end });
+        }
+
+        Ok(Span { start, end })
+    }
+
+    /// Creates a new `Span` from a `Range`. This is the fallible version of the `From<Range<SpanValue>>` impl,
+    /// which can't have a matching `TryFrom` impl since core already implements `TryFrom` for every `From` impl.
+    ///
+    /// Returns [`SpanError::NegativeSize`] if the range ends before it starts, since spans can't have a negative
+    /// length.
+    #[inline(always)]
+    pub const fn try_from_range(range: Range<SpanValue>) -> Result<Self, SpanError> {
+        Self::try_new_from(range.start, range.end)
+    }
+
+    /// Creates a new `Span` that starts at `start` and runs to the end of a buffer that ends at `end_of_buffer`.
+    ///
+    /// # Panics
+    /// Panics if start is greater than `end_of_buffer`, since spans can't have a negative length.
+    #[inline(always)]
+    pub const fn to_end(start: SpanValue, end_of_buffer: SpanValue) -> Self {
+        Self::new_from(start, end_of_buffer)
+    }
+
+    /// Creates a new `Span` that starts at the beginning of a buffer and ends at `end`.
+    #[inline(always)]
+    pub const fn from_start(end: SpanValue) -> Self {
+        Span { start: 0, end }
+    }
+
+    /// Creates a new `Span` from a `RangeFrom`, ending it at `end`. `RangeFrom` has no end, so unlike `RangeTo`
+    /// it can't be converted with `From`, and the same goes for `RangeFull`.
+    ///
+    /// # Panics
+    /// Panics if the range starts after `end`, since spans can't have a negative length.
+    #[inline(always)]
+    pub const fn from_range_from(range: RangeFrom<SpanValue>, end: SpanValue) -> Self {
+        Self::new_from(range.start, end)
+    }
+
+    /// Creates a new `Span` from a `Range<usize>`, like the byte spans `logos` lexers return from `lexer.span()`.
+    ///
+    /// # Panics
+    /// Panics if either end of the range doesn't fit in a `SpanValue`, or if the range ends before it starts.
+    #[inline(always)]
+    pub fn from_logos(range: Range<usize>) -> Self {
+        Self::try_from_logos(range).expect("cannot convert range into a span")
+    }
+
+    /// Creates a new `Span` from a `Range<usize>`, like the byte spans `logos` lexers return from `lexer.span()`.
+    ///
+    /// Returns [`SpanError::Overflow`] if either end of the range doesn't fit in a `SpanValue`, and
+    /// [`SpanError::NegativeSize`] if the range ends before it starts.
+    #[inline(always)]
+    pub fn try_from_logos(range: Range<usize>) -> Result<Self, SpanError> {
+        Self::try_new_from(
+            SpanValue::from_usize(range.start).ok_or(SpanError::Overflow)?,
+            SpanValue::from_usize(range.end).ok_or(SpanError::Overflow)?,
+        )
+    }
+
+    /// Grows the span from the front. This moves the end value up by `amount`.
+    ///
+    /// # Panics
+    /// Panics if the end value would overflow `SpanValue`, so the span can never wrap around to a negative size.
+    /// Use [`checked_grow_front`](Span::checked_grow_front) if that can happen.
+    #[inline(always)]
+    pub fn grow_front(&mut self, amount: SpanValue) {
+        self.end = self
+            .end
+            .checked_add(amount)
+            .expect("cannot create a span with an end value past SpanValue::MAX");
+    }
+
+    /// Returns a span that is grown from the front. This moves the end value up by `amount`.
+    ///
+    /// # Panics
+    /// Panics if the end value would overflow `SpanValue`, so the span can never wrap around to a negative size.
+    /// Use [`checked_grow_front`](Span::checked_grow_front) if that can happen.
+    #[inline(always)]
+    pub fn with_grow_front(&self, amount: SpanValue) -> Self {
+        let mut new = *self;
+        new.grow_front(amount);
+        new
+    }
+
+    /// Returns a span that is grown from the front. This moves the end value up by `amount`.
+    ///
+    /// Returns `None` if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    pub fn checked_grow_front(&self, amount: SpanValue) -> Option<Span> {
+        Some(Span {
+            start: self.start,
+            end: self.end.checked_add(amount)?,
+        })
+    }
+
+    /// Returns a span that is grown from the front. This moves the end value up by `amount`, stopping at
+    /// `SpanValue::MAX`.
+    #[inline(always)]
+    pub fn saturating_grow_front(&self, amount: SpanValue) -> Span {
+        Span {
+            start: self.start,
+            end: self.end.saturating_add(amount),
+        }
+    }
+
+    /// Grows the span from the front by up to `amount`, without moving the end value past `max`. Returns how much
+    /// the end value actually moved. If the end value is already past `max`, the span doesn't change.
+    #[inline(always)]
+    pub fn grow_front_clamped(&mut self, amount: SpanValue, max: SpanValue) -> SpanValue {
+        let end = self.end.saturating_add(amount).min(max).max(self.end);
+        let grown = end - self.end;
+        self.end = end;
+        grown
+    }
+
+    /// Grows the span from the back. This moves the start value back by `amount`.
+    ///
+    /// # Panics
+    /// Panics if the start of the span is less than `amount`, since spans can't have a negative start value,
+    #[inline(always)]
+    pub fn grow_back(&mut self, amount: SpanValue) {
+        assert!(
+            self.start >= amount,
+            "cannot create a span with a negative start value"
+        );
+        self.start -= amount;
+    }
+
+    /// Returns a span that is grown from the back. This moves the start value back by `amount`.
+    ///
+    /// # Panics
+    /// Panics if the start of the span is less than `amount`, since spans can't have a negative start value,
+    #[inline(always)]
+    pub fn with_grow_back(&self, amount: SpanValue) -> Self {
+        assert!(
+            self.start >= amount,
+            "cannot create a span with a negative start value"
+        );
+        let mut new = *self;
+        new.start -= amount;
+        new
+    }
+
+    /// Returns a span that is grown from the back. This moves the start value back by `amount`.
+    ///
+    /// Returns `None` if the start of the span is less than `amount`, since spans can't have a negative start value.
+    #[inline(always)]
+    pub fn checked_grow_back(&self, amount: SpanValue) -> Option<Span> {
+        Some(Span {
+            start: self.start.checked_sub(amount)?,
+            end: self.end,
+        })
+    }
+
+    /// Returns a span that is grown from the back. This moves the start value back by `amount`. This is the
+    /// non-panicking version of [`with_grow_back`](Span::with_grow_back), like
+    /// [`checked_grow_back`](Span::checked_grow_back) but with an error instead of `None`.
+    ///
+    /// Returns [`SpanError::Underflow`] if the start of the span is less than `amount`, since spans can't have a
+    /// negative start value.
+    #[inline(always)]
+    pub fn try_grow_back(&self, amount: SpanValue) -> Result<Span, SpanError> {
+        self.checked_grow_back(amount).ok_or(SpanError::Underflow)
+    }
+
+    /// Returns a span that is grown from the back. This moves the start value back by `amount`, stopping at `0`.
+    #[inline(always)]
+    pub fn saturating_grow_back(&self, amount: SpanValue) -> Span {
+        Span {
+            start: self.start.saturating_sub(amount),
+            end: self.end,
+        }
+    }
+
+    /// Grows the span from the back by up to `amount`, without moving the start value below `0`. Returns how much
+    /// the start value actually moved.
+    #[inline(always)]
+    pub fn grow_back_clamped(&mut self, amount: SpanValue) -> SpanValue {
+        let grown = amount.min(self.start);
+        self.start -= grown;
+        grown
+    }
+
+    /// Shrinks the span from the back. This moves the start value up by `amount`.
+    ///
+    /// # Panics
+    /// Panics if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn shrink_back(&mut self, amount: SpanValue) {
+        assert!(self.len() >= amount, "cannot create negative-size span");
+        self.start += amount;
+    }
+
+    /// Returns a span that is shrunk from the back. This moves the start value up by `amount`.
+    ///
+    /// # Panics
+    /// Panics if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn with_shrink_back(&self, amount: SpanValue) -> Self {
+        assert!(self.len() >= amount, "cannot create negative-size span");
+        let mut new = *self;
+        new.start += amount;
+        new
+    }
+
+    /// Returns a span that is shrunk from the back. This moves the start value up by `amount`.
+    ///
+    /// Returns `None` if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn checked_shrink_back(&self, amount: SpanValue) -> Option<Span> {
+        (self.len() >= amount).then(|| Span {
+            start: self.start + amount,
+            end: self.end,
+        })
+    }
+
+    /// Returns a span that is shrunk from the back. This moves the start value up by `amount`, stopping at the end
+    /// value, which leaves an empty span.
+    #[inline(always)]
+    pub fn saturating_shrink_back(&self, amount: SpanValue) -> Span {
+        Span {
+            start: self.start + amount.min(self.len()),
+            end: self.end,
+        }
+    }
+
+    /// Shrinks the span from the front. This moves the end value back by `amount`.
+    ///
+    /// # Panics
+    /// This method will panic if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn shrink_front(&mut self, amount: SpanValue) {
+        assert!(self.len() >= amount, "cannot create negative-size span");
+        self.end -= amount;
+    }
+
+    /// Returns a span shrunk from the front. This moves the end value back by `amount`.
+    ///
+    /// # Panics
+    /// This method will panic if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn with_shrink_front(&self, amount: SpanValue) -> Self {
+        assert!(self.len() >= amount, "cannot create negative-size span");
+        let mut new = *self;
+        new.end -= amount;
+        new
+    }
+
+    /// Returns a span shrunk from the front. This moves the end value back by `amount`.
+    ///
+    /// Returns `None` if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn checked_shrink_front(&self, amount: SpanValue) -> Option<Span> {
+        (self.len() >= amount).then(|| Span {
+            start: self.start,
+            end: self.end - amount,
+        })
+    }
+
+    /// Returns a span shrunk from the front. This moves the end value back by `amount`, stopping at the start
+    /// value, which leaves an empty span.
+    #[inline(always)]
+    pub fn saturating_shrink_front(&self, amount: SpanValue) -> Span {
+        Span {
+            start: self.start,
+            end: self.end - amount.min(self.len()),
+        }
+    }
+
+    /// Returns a span that is shrunk from the front so it is at most `max` units long. Spans that are already
+    /// short enough, including empty spans, are returned unchanged.
+    #[inline(always)]
+    pub fn shrink_to_len(&self, max: SpanValue) -> Span {
+        Span {
+            start: self.start,
+            end: self.start + self.len().min(max),
+        }
+    }
+
+    /// Splits the span into a head and a tail that are at most `max` units long together, dropping the middle, like
+    /// when showing the first and last parts of a long span with an ellipsis between them.
+    ///
+    /// The tail gets `max / 2` units and the head gets the rest, so for an odd `max` the head is one unit longer
+    /// than the tail. If the span is already at most `max` units long, the head is the whole span and the tail is an
+    /// empty span at its end.
+    #[inline(always)]
+    pub fn clamp_len_centered(&self, max: SpanValue) -> (Span, Span) {
+        if self.len() <= max {
+            return (*self, Span::empty_at(self.end));
+        }
+
+        let tail_len = max / 2;
+        (
+            Span {
+                start: self.start,
+                end: self.start + (max - tail_len),
+            },
+            Span {
+                start: self.end - tail_len,
+                end: self.end,
+            },
+        )
+    }
+
+    /// Grows the span by `amount` on the side given by `direction`, like [`grow_front`](Span::grow_front) or
+    /// [`grow_back`](Span::grow_back).
+    ///
+    /// # Panics
+    /// Panics if the end value would overflow `SpanValue`, or if the start value would become negative.
+    #[inline(always)]
+    pub fn extend(&mut self, direction: Direction, amount: SpanValue) {
+        match direction {
+            Direction::Front => self.grow_front(amount),
+            Direction::Back => self.grow_back(amount),
+        }
+    }
+
+    /// Shrinks the span by `amount` on the side given by `direction`, like [`shrink_front`](Span::shrink_front)
+    /// or [`shrink_back`](Span::shrink_back).
+    ///
+    /// # Panics
+    /// Panics if the size of the `Span` is less than `amount`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn shrink(&mut self, direction: Direction, amount: SpanValue) {
+        match direction {
+            Direction::Front => self.shrink_front(amount),
+            Direction::Back => self.shrink_back(amount),
+        }
+    }
+
+    /// Returns a span that is moved by `delta`, which is added to both the start and end values.
+    ///
+    /// # Panics
+    /// Panics if the start value would become negative, or if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    pub fn shift(&self, delta: SpanDelta) -> Span {
+        self.checked_shift(delta)
+            .expect("cannot shift a span out of the range of SpanValue")
+    }
+
+    /// Returns a span that is moved by `delta`, which is added to both the start and end values.
+    ///
+    /// Returns `None` if the start value would become negative, or if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    pub fn checked_shift(&self, delta: SpanDelta) -> Option<Span> {
+        Some(Span {
+            start: self.start.checked_add_signed(delta)?,
+            end: self.end.checked_add_signed(delta)?,
+        })
+    }
+
+    /// Returns a span that is moved up by `amount`, which is added to both the start and end values. This is the
+    /// checked version of `span + amount`.
+    ///
+    /// Returns `None` if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    pub fn checked_add(&self, amount: SpanValue) -> Option<Span> {
+        Some(Span {
+            start: self.start.checked_add(amount)?,
+            end: self.end.checked_add(amount)?,
+        })
+    }
+
+    /// Returns a span that is moved back by `amount`, which is subtracted from both the start and end values. This
+    /// is the checked version of `span - amount`.
+    ///
+    /// Returns `None` if the start value would become negative.
+    #[inline(always)]
+    pub fn checked_sub(&self, amount: SpanValue) -> Option<Span> {
+        Some(Span {
+            start: self.start.checked_sub(amount)?,
+            end: self.end.checked_sub(amount)?,
+        })
+    }
+
+    /// Returns a span with both the start and end values multiplied by `factor`, like when converting between
+    /// units of different sizes. This is a linear transform, so empty spans stay empty.
+    ///
+    /// # Panics
+    /// Panics if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    pub fn scale(&self, factor: SpanValue) -> Span {
+        self.checked_scale(factor)
+            .expect("cannot scale a span out of the range of SpanValue")
+    }
+
+    /// Returns a span with both the start and end values multiplied by `factor`, like when converting between
+    /// units of different sizes. This is a linear transform, so empty spans stay empty.
+    ///
+    /// Returns `None` if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    pub fn checked_scale(&self, factor: SpanValue) -> Option<Span> {
+        Some(Span {
+            start: self.start.checked_mul(factor)?,
+            end: self.end.checked_mul(factor)?,
+        })
+    }
+
+    /// Returns a span with `f` applied to both the start and end values, like when mapping positions through a
+    /// source map. [`shift`](Span::shift) and [`scale`](Span::scale) are special cases of this. `f` should be
+    /// monotonic, meaning it never maps a larger value to a smaller one, so that the result stays well-formed.
+    ///
+    /// # Panics
+    /// Panics if the mapped end is before the mapped start.
+    #[inline(always)]
+    pub fn map_endpoints<F: Fn(SpanValue) -> SpanValue>(&self, f: F) -> Span {
+        Span::new_from(f(self.start), f(self.end))
+    }
+
+    /// Returns a span with `f` applied to both the start and end values, like when mapping positions through a
+    /// source map. `f` should be monotonic, meaning it never maps a larger value to a smaller one.
+    ///
+    /// Returns [`SpanError::NegativeSize`] if the mapped end is before the mapped start.
+    #[inline(always)]
+    pub fn try_map_endpoints<F: Fn(SpanValue) -> SpanValue>(
+        &self,
+        f: F,
+    ) -> Result<Span, SpanError> {
+        Span::try_new_from(f(self.start), f(self.end))
+    }
+
+    /// Rebases a span that is relative to `base` into the coordinates `base` is in, by moving it up by
+    /// `base.start`. For example, a token at `1..3` in a slice at `10..20` of a file is at `11..13` in the file.
+    ///
+    /// # Panics
+    /// Panics if the rebased span would end after `base`.
+    #[inline(always)]
+    pub fn relative_to(&self, base: &Span) -> Span {
+        let rebased = Span {
+            start: self
+                .start
+                .checked_add(base.start)
+                .expect("span doesn't fit inside its base"),
+            end: self
+                .end
+                .checked_add(base.start)
+                .expect("span doesn't fit inside its base"),
+        };
+        rebased.debug_assert_well_formed();
+        assert!(rebased.end <= base.end, "span doesn't fit inside its base");
+        rebased
+    }
+
+    /// Rebases a span that is in the same coordinates as `base` to be relative to `base`, by moving it back by
+    /// `base.start`. This is the inverse of [`relative_to`](Span::relative_to).
+    ///
+    /// # Panics
+    /// Panics if the span doesn't lie inside `base`.
+    #[inline(always)]
+    pub fn absolute_from(&self, base: &Span) -> Span {
+        assert!(base.contains_span(self), "span doesn't fit inside its base");
+        Span {
+            start: self.start - base.start,
+            end: self.end - base.start,
+        }
+    }
+
+    /// Expands the span so it covers `point`. If `point` is before the span, the start value moves back to
+    /// `point`, and if it is at or after the end, the end value moves up to `point + 1`. If the span already covers
+    /// `point`, nothing changes.
+    ///
+    /// # Panics
+    /// Panics if `point` is `SpanValue::MAX` and the span doesn't already cover it, since the end value would
+    /// overflow `SpanValue`.
+    #[inline(always)]
+    pub fn expand_to_include(&mut self, point: SpanValue) {
+        if point < self.start {
+            self.start = point;
+        } else if point >= self.end {
+            self.end = point
+                .checked_add(1)
+                .expect("cannot create a span with an end value past SpanValue::MAX");
+        }
+        self.debug_assert_well_formed();
+    }
+
+    /// Returns a span that is expanded to cover `point`. If `point` is before the span, the start value moves
+    /// back to `point`, and if it is at or after the end, the end value moves up to `point + 1`. If the span
+    /// already covers `point`, it is returned unchanged.
+    ///
+    /// # Panics
+    /// Panics if `point` is `SpanValue::MAX` and the span doesn't already cover it, since the end value would
+    /// overflow `SpanValue`.
+    #[inline(always)]
+    pub fn with_expand_to_include(&self, point: SpanValue) -> Self {
+        let mut new = *self;
+        new.expand_to_include(point);
+        new
+    }
+
+    /// Splits the span in two at `offset`, which is relative to the start of the span. The first span covers
+    /// `start..start + offset`, and the second covers `start + offset..end`.
+    ///
+    /// # Panics
+    /// Panics if `offset` is greater than the size of the `Span`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn split_at(&self, offset: SpanValue) -> (Span, Span) {
+        self.checked_split_at(offset)
+            .expect("cannot create negative-size span")
+    }
+
+    /// Splits the span in two at `offset`, which is relative to the start of the span. The first span covers
+    /// `start..start + offset`, and the second covers `start + offset..end`.
+    ///
+    /// Returns `None` if `offset` is greater than the size of the `Span`.
+    #[inline(always)]
+    pub fn checked_split_at(&self, offset: SpanValue) -> Option<(Span, Span)> {
+        if offset > self.len() {
+            return None;
+        }
+
+        let middle = self.start + offset;
+        Some((
+            Span {
+                start: self.start,
+                end: middle,
+            },
+            Span {
+                start: middle,
+                end: self.end,
+            },
+        ))
+    }
+
+    /// Splits the span in two at `offset`, which is relative to the start of the span. `self` is truncated to
+    /// `start..start + offset`, and the rest of the span, `start + offset..end`, is returned.
+    ///
+    /// # Panics
+    /// Panics if `offset` is greater than the size of the `Span`, since a `Span`'s size can't be negative.
+    #[inline(always)]
+    pub fn split_off(&mut self, offset: SpanValue) -> Span {
+        let (head, tail) = self.split_at(offset);
+        *self = head;
+        tail
+    }
+
+    /// Returns an iterator over consecutive sub-spans of `size` units that together cover the span. The last
+    /// chunk is shorter than `size` if the span's size isn't a multiple of `size`. An empty span has no chunks.
+    ///
+    /// # Panics
+    /// Panics if `size` is `0`.
+    pub fn chunks(&self, size: SpanValue) -> impl Iterator<Item = Span> + use<> {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        let mut rest = *self;
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+
+            let (chunk, tail) = rest.split_at(size.min(rest.len()));
+            rest = tail;
+            Some(chunk)
+        })
+    }
+
+    /// Returns an iterator over every sub-span of `size` units, each one unit further along than the last. For
+    /// example, the windows of size `2` of `0..5` are `0..2`, `1..3`, `2..4` and `3..5`. If `size` is greater than
+    /// the span's size, there are no windows.
+    ///
+    /// # Panics
+    /// Panics if `size` is `0`.
+    pub fn windows(&self, size: SpanValue) -> impl Iterator<Item = Span> + use<> {
+        assert!(size != 0, "window size must be non-zero");
+
+        let starts_end = if size <= self.len() {
+            self.end - size + 1
+        } else {
+            self.start
+        };
+        (self.start..starts_end).map(move |start| Span {
+            start,
+            end: start + size,
+        })
+    }
+
+    /// Checks if a `Span`'s size is `0`. Returns `true` if `0`, and false if anything else.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the span's end is at or after its start. Spans built through this crate always are, but spans
+    /// made by writing to the public fields, or cast from bytes with the `bytemuck` feature, might not be.
+    #[inline(always)]
+    pub const fn is_well_formed(&self) -> bool {
+        self.end >= self.start
+    }
+
+    /// Checks that the span is [well-formed](Span::is_well_formed) in debug builds, and does nothing in release
+    /// builds.
+    ///
+    /// # Panics
+    /// Panics in debug builds if the span's end is before its start.
+    #[inline(always)]
+    #[track_caller]
+    pub const fn debug_assert_well_formed(&self) {
+        debug_assert!(self.is_well_formed(), "span has a negative size");
+    }
+
+    /// Gets the length of a `Span`.
+    #[inline(always)]
+    pub const fn len(&self) -> SpanValue {
+        self.end - self.start
+    }
+
+    /// Gets the length of a `Span`, or `None` if its end is before its start. Unlike [`len`](Span::len), this
+    /// doesn't overflow on spans that aren't [well-formed](Span::is_well_formed).
+    #[inline(always)]
+    pub const fn checked_len(&self) -> Option<SpanValue> {
+        self.end.checked_sub(self.start)
+    }
+
+    /// Gets the length of a `Span` as a [`NonZero`], or `None` if the span is empty.
+    #[inline(always)]
+    pub const fn len_nonzero(&self) -> Option<NonZero<SpanValue>> {
+        NonZero::new(self.len())
+    }
+
+    /// Feeds only the start of the span into `state`, so spans starting at the same position hash the same
+    /// regardless of their end. This hashes `start` the same way `Span`'s `Hash` impl does.
+    #[inline(always)]
+    pub fn hash_start<H: Hasher>(&self, state: &mut H) {
+        self.start.hash(state);
+    }
+
+    /// Gets the span as a `Range`, from `start` to `end`.
+    #[inline(always)]
+    pub const fn as_range(&self) -> Range<SpanValue> {
+        self.start..self.end
+    }
+
+    /// Gets the span as a `Range<usize>`, for indexing slices and strings.
+    ///
+    /// # Panics
+    /// Panics if either end doesn't fit in a `usize`. This can only happen for `u128` spans, or for `u64` spans on
+    /// platforms where `usize` is smaller than 64 bits.
+    #[inline(always)]
+    pub fn as_usize_range(&self) -> Range<usize> {
+        let to_usize = |value: SpanValue| value.to_usize().expect("span doesn't fit in usize");
+        to_usize(self.start)..to_usize(self.end)
+    }
+
+    /// Gets the middle of a `Span`, which is `start + len / 2`.
+    ///
+    /// For odd-length spans, the midpoint is rounded down towards the start, so `0..3` has a midpoint of `1`.
+    #[inline(always)]
+    pub fn midpoint(&self) -> SpanValue {
+        self.start + self.len() / 2
+    }
+
+    /// Moves the start of the span up to its end, leaving an empty span at the old end, and returns the old span.
+    ///
+    /// This is the same as [`advance`](Span::advance). To replace the span with `0..0` instead, use
+    /// [`take`](Span::take).
+    #[deprecated(note = "use `Span::advance` instead, which does the same thing")]
+    #[inline(always)]
+    pub fn reset(&mut self) -> Self {
+        self.advance()
+    }
+
+    /// Moves the start of the span up to its end, leaving an empty span at the old end, and returns the old span.
+    ///
+    /// This is useful when scanning, to consume the span covering the current token and start the next one where
+    /// it ended.
+    #[inline(always)]
+    pub fn advance(&mut self) -> Self {
+        let span = *self;
+        self.start = self.end;
+        span
+    }
+
+    /// Replaces the span with an empty span at `0`, like [`Span::new`], and returns the old span. This is the same
+    /// as [`core::mem::take`], and is useful for draining a span that accumulates positions.
+    #[inline(always)]
+    pub fn take(&mut self) -> Self {
+        core::mem::take(self)
+    }
+
+    /// Returns the area shared by `self` and `other`, or `None` if they don't overlap.
+    ///
+    /// Spans that only touch, like `0..5` and `5..10`, don't overlap. An empty span that lies inside `other` (or on
+    /// one of its edges) has an empty intersection at its position.
+    #[inline(always)]
+    pub fn intersection(&self, other: &Span) -> Option<Span> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+
+        if start < end || (start == end && (self.is_empty() || other.is_empty())) {
+            Some(Span { start, end })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the number of positions `self` and `other` share, or `0` if they don't overlap. This is the length of
+    /// their [`intersection`](Span::intersection), without building it.
+    #[inline(always)]
+    pub fn overlap_len(&self, other: &Span) -> SpanValue {
+        self.end
+            .min(other.end)
+            .saturating_sub(self.start.max(other.start))
+    }
+
+    /// Returns the smallest span covering both `self` and `other`.
+    ///
+    /// Unlike a set union, this always returns a single span: any gap between `self` and `other` is swallowed
+    /// and becomes part of the result. Empty spans still count, so the result always reaches their position.
+    #[inline(always)]
+    pub fn union(&self, other: &Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    /// Returns the smallest span covering both `self` and `other` if they overlap or are adjacent, or `None` if
+    /// there is a gap between them. Unlike [`union`](Span::union), this never swallows positions that neither span
+    /// covers.
+    #[inline(always)]
+    pub fn merge_if_adjacent(&self, other: &Span) -> Option<Span> {
+        (self.start <= other.end && other.start <= self.end).then(|| self.union(other))
+    }
+
+    /// Returns the part of the span that lies inside `bounds`.
+    ///
+    /// If the span doesn't overlap `bounds` at all, the result is an empty span at the edge of `bounds` nearest to
+    /// it. The result is never a negative-size span.
+    #[inline(always)]
+    pub fn clamp(&self, bounds: &Span) -> Span {
+        Span {
+            start: self.start.clamp(bounds.start, bounds.end),
+            end: self.end.clamp(bounds.start, bounds.end),
+        }
+    }
+
+    /// Creates a span covering `point` and up to `radius` positions on either side of it, clamped to `bounds`, like
+    /// the context window around an error. Before clamping, the span is `point - radius..point + radius + 1`, with
+    /// both ends saturating instead of overflowing.
+    #[inline(always)]
+    pub fn center_on(point: SpanValue, radius: SpanValue, bounds: &Span) -> Span {
+        Span {
+            start: point.saturating_sub(radius),
+            end: point.saturating_add(radius).saturating_add(1),
+        }
+        .clamp(bounds)
+    }
+
+    /// Returns the span grown by `amount` on both sides, clamped to `bounds`. Unlike
+    /// `with_grow_back(amount).with_grow_front(amount)`, padding near the edges of `bounds` or of `SpanValue` is
+    /// clamped instead of panicking or wrapping around.
+    #[inline(always)]
+    pub fn pad(&self, amount: SpanValue, bounds: &Span) -> Span {
+        Span {
+            start: self.start.saturating_sub(amount),
+            end: self.end.saturating_add(amount),
+        }
+        .clamp(bounds)
+    }
+
+    /// Returns the smallest span covering every span in `spans`, or `None` if there are no spans. Like
+    /// [`union`](Span::union), any gaps between the spans are swallowed.
+    pub fn join<I: IntoIterator<Item = Span>>(spans: I) -> Option<Span> {
+        spans.into_iter().reduce(|joined, span| joined.union(&span))
+    }
+
+    /// Compares two spans by their start, so spans can be sorted with `spans.sort_by(Span::cmp_by_start)`. This is a
+    /// total order on the start values, unlike `Span`'s partial order.
+    #[inline(always)]
+    pub fn cmp_by_start(a: &Span, b: &Span) -> Ordering {
+        a.start.cmp(&b.start)
+    }
+
+    /// Compares two spans by their end, so spans can be sorted with `spans.sort_by(Span::cmp_by_end)`. This is a
+    /// total order on the end values, unlike `Span`'s partial order.
+    #[inline(always)]
+    pub fn cmp_by_end(a: &Span, b: &Span) -> Ordering {
+        a.end.cmp(&b.end)
+    }
+
+    /// Compares two spans by their length, so spans can be sorted with `spans.sort_by(Span::cmp_by_len)`. This is a
+    /// total order on the lengths, unlike `Span`'s partial order.
+    #[inline(always)]
+    pub fn cmp_by_len(a: &Span, b: &Span) -> Ordering {
+        a.len().cmp(&b.len())
+    }
+
+    /// Checks if `self` and `other` share at least one unit of area.
+    ///
+    /// Adjacent spans, like `2..4` and `4..6`, don't overlap, and an empty span never overlaps anything.
+    #[inline(always)]
+    pub fn overlaps(&self, other: &Span) -> bool {
+        self.start.max(other.start) < self.end.min(other.end)
+    }
+
+    /// Checks if `self` and `other` share no area. This is the opposite of [`overlaps`](Span::overlaps).
+    ///
+    /// Adjacent spans are disjoint, and an empty span is disjoint from everything.
+    #[inline(always)]
+    pub fn is_disjoint(&self, other: &Span) -> bool {
+        !self.overlaps(other)
+    }
+
+    /// Checks if the span and `range` share at least one unit of area. This is the same as
+    /// [`overlaps`](Span::overlaps), without converting `range` into a `Span`.
+    #[inline(always)]
+    pub fn intersects_range(&self, range: &Range<SpanValue>) -> bool {
+        self.start.max(range.start) < self.end.min(range.end)
+    }
+
+    /// Checks if `range` lies entirely inside the span. This is the same as
+    /// [`contains_span`](Span::contains_span), without converting `range` into a `Span`.
+    #[inline(always)]
+    pub fn contains_range(&self, range: &Range<SpanValue>) -> bool {
+        self.start <= range.start && range.end <= self.end
+    }
+
+    /// Checks if `range` lies entirely inside the span. The span's end is exclusive but the end of `range` is
+    /// inclusive, so the last position in `range` has to be before the span's end: `0..=4` lies inside `0..5`, but
+    /// `0..=5` doesn't.
+    #[inline(always)]
+    pub fn contains_range_inclusive(&self, range: &RangeInclusive<SpanValue>) -> bool {
+        self.start <= *range.start() && *range.end() < self.end
+    }
+
+    /// Checks if `self` lies entirely before `other`, meaning `self.end <= other.start`. Adjacent spans count, so
+    /// `0..5` is before `5..10`.
+    #[inline(always)]
+    pub fn is_before(&self, other: &Span) -> bool {
+        self.end <= other.start
+    }
+
+    /// Checks if `self` lies entirely after `other`, meaning `other.end <= self.start`. Adjacent spans count, so
+    /// `5..10` is after `0..5`.
+    #[inline(always)]
+    pub fn is_after(&self, other: &Span) -> bool {
+        other.is_before(self)
+    }
+
+    /// Checks if `self` and `other` are back-to-back with no gap between them, meaning one ends exactly where the
+    /// other starts. Overlapping spans are never adjacent.
+    #[inline(always)]
+    pub fn is_adjacent(&self, other: &Span) -> bool {
+        self.end == other.start || other.end == self.start
+    }
+
+    /// Returns the span strictly between `self` and `other`, running from the end of the earlier span to the start
+    /// of the later one. The order of the arguments doesn't matter.
+    ///
+    /// Returns `None` if the spans overlap or are adjacent, since there is no gap between them.
+    #[inline(always)]
+    pub fn gap(&self, other: &Span) -> Option<Span> {
+        let start = self.end.min(other.end);
+        let end = self.start.max(other.start);
+
+        if start < end {
+            Some(Span { start, end })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of units between `self` and `other`, which is the length of their [`gap`](Span::gap). The
+    /// order of the arguments doesn't matter.
+    ///
+    /// Returns `0` if the spans overlap or are adjacent.
+    #[inline(always)]
+    pub fn distance(&self, other: &Span) -> SpanValue {
+        self.start
+            .max(other.start)
+            .saturating_sub(self.end.min(other.end))
+    }
+
+    /// Snaps `point` to whichever of `start` and `end` is closer to it. If `point` is exactly as far from `start` as
+    /// from `end`, like the midpoint of a span with an even length, `start` is returned.
+    #[inline(always)]
+    pub fn nearest_boundary(&self, point: SpanValue) -> SpanValue {
+        if point.abs_diff(self.start) <= point.abs_diff(self.end) {
+            self.start
+        } else {
+            self.end
+        }
+    }
+
+    /// Removes `other` from the span, returning the part of `self` before `other` and the part after it.
+    ///
+    /// Parts that would be empty are `None`, so if `other` covers all of `self`, both are `None`. If the spans
+    /// don't overlap, all of `self` is returned as the part before or after `other`, depending on where it lies.
+    /// This includes an empty `other` inside `self`, which doesn't split it: `self` is returned as the part before
+    /// it, unless `other` is at the start of `self`.
+    #[inline(always)]
+    pub fn difference(&self, other: &Span) -> (Option<Span>, Option<Span>) {
+        if !self.overlaps(other) {
+            let span = (!self.is_empty()).then_some(*self);
+            return if other.end <= self.start {
+                (None, span)
+            } else {
+                (span, None)
+            };
+        }
+
+        let before = Span {
+            start: self.start,
+            end: self.end.min(other.start),
+        };
+        let after = Span {
+            start: self.start.max(other.end),
+            end: self.end,
+        };
+
+        (
+            (before.start < before.end).then_some(before),
+            (after.start < after.end).then_some(after),
+        )
+    }
+
+    /// Returns the parts covered by exactly one of `self` and `other`, sorted by their start.
+    ///
+    /// Parts that would be empty are `None`, so if the spans are equal, both are `None`. If the spans don't overlap,
+    /// both spans are returned as they are.
+    #[inline(always)]
+    pub fn symmetric_difference(&self, other: &Span) -> (Option<Span>, Option<Span>) {
+        let (first, second) = if self.overlaps(other) {
+            (
+                Span {
+                    start: self.start.min(other.start),
+                    end: self.start.max(other.start),
+                },
+                Span {
+                    start: self.end.min(other.end),
+                    end: self.end.max(other.end),
+                },
+            )
+        } else if self.start <= other.start {
+            (*self, *other)
+        } else {
+            (*other, *self)
+        };
+
+        (
+            (!first.is_empty()).then_some(first),
+            (!second.is_empty()).then_some(second),
+        )
+    }
+
+    /// Checks if `point` lies inside the span, meaning `start <= point < end`. The start is included and the end
+    /// is not, like a `Range`, so an empty span contains no points. Use
+    /// [`contains_point_inclusive`](Span::contains_point_inclusive) to include the end as well.
+    #[inline(always)]
+    pub fn contains_point(&self, point: SpanValue) -> bool {
+        self.start <= point && point < self.end
+    }
+
+    /// Checks if `point` lies inside the span or on its end, meaning `start <= point <= end`. Both the start and
+    /// the end are included, like a `RangeInclusive`, so an empty span contains the point it is at.
+    #[inline(always)]
+    pub fn contains_point_inclusive(&self, point: SpanValue) -> bool {
+        self.start <= point && point <= self.end
+    }
+
+    /// Checks if `other` lies entirely inside the span, meaning `self.start <= other.start` and
+    /// `other.end <= self.end`.
+    ///
+    /// An empty `other` is contained if its position is inside `self` or on one of its edges. If `self` is empty,
+    /// the only span it contains is an empty span at the same position.
+    #[inline(always)]
+    pub fn contains_span(&self, other: &Span) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Applies the span to `string`, with `start` and `end` corresponding to char indexes. The span may end at the
+    /// end of `string`.
+    ///
+    /// # Panics
+    /// Panics if `string` is shorter than the end of the span.
+    pub fn apply<'a>(&self, string: &'a str) -> &'a str {
+        self.try_apply(string)
+            .expect("string is too short to have the span applied")
+    }
+
+    /// Applies the span to `string`, with `start` and `end` corresponding to char indexes.
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `string` is shorter than the end of the span, and
+    /// [`SpanError::NegativeSize`] if the span isn't [well-formed](Span::is_well_formed).
+    pub fn try_apply<'a>(&self, string: &'a str) -> Result<&'a str, SpanError> {
+        let (start, end) = self.try_byte_bounds(string)?;
+        Ok(&string[start..end])
+    }
+
+    /// Converts the span from char indexes into byte indexes in `source`.
+    ///
+    /// # Panics
+    /// Panics if `source` is shorter than the end of the span, or if a byte index doesn't fit in a `SpanValue`.
+    pub fn to_byte_span(&self, source: &str) -> Span {
+        self.try_to_byte_span(source)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Converts the span from char indexes into byte indexes in `source`.
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `source` is shorter than the end of the span, [`SpanError::Overflow`] if a
+    /// byte index doesn't fit in a `SpanValue`, and [`SpanError::NegativeSize`] if the span isn't
+    /// [well-formed](Span::is_well_formed).
+    pub fn try_to_byte_span(&self, source: &str) -> Result<Span, SpanError> {
+        let (start, end) = self.try_byte_bounds(source)?;
+        Ok(Span {
+            start: SpanValue::from_usize(start).ok_or(SpanError::Overflow)?,
+            end: SpanValue::from_usize(end).ok_or(SpanError::Overflow)?,
+        })
+    }
+
+    /// Converts the span from byte indexes into char indexes in `source`.
+    ///
+    /// # Panics
+    /// Panics if `source` is shorter than the end of the span, or if `start` or `end` isn't on a char boundary.
+    pub fn to_char_span(&self, source: &str) -> Span {
+        self.try_to_char_span(source)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Converts the span from byte indexes into char indexes in `source`.
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `source` is shorter than the end of the span,
+    /// [`SpanError::NotCharBoundary`] if `start` or `end` isn't on a char boundary, and [`SpanError::NegativeSize`] if
+    /// the span isn't [well-formed](Span::is_well_formed).
+    #[allow(clippy::unnecessary_cast)]
+    pub fn try_to_char_span(&self, source: &str) -> Result<Span, SpanError> {
+        let (start, end) = self.str_byte_indexes(source)?;
+
+        // A string never has more chars than bytes, so the char indexes always fit in a `SpanValue`.
+        let char_start = source[..start].chars().count();
+        let char_end = char_start + source[start..end].chars().count();
+        Ok(Span {
+            start: char_start as SpanValue,
+            end: char_end as SpanValue,
+        })
+    }
+
+    /// Finds the byte offsets of the char indexes `start` and `end` in `source`, without slicing it. This is the
+    /// range [`apply`](Span::apply) slices, for passing to tools that take byte offsets.
+    ///
+    /// # Panics
+    /// Panics if `source` is shorter than the end of the span.
+    pub fn byte_bounds(&self, source: &str) -> (usize, usize) {
+        self.try_byte_bounds(source)
+            .expect("string is too short to have the span applied")
+    }
+
+    /// Finds the byte offsets of the char indexes `start` and `end` in `source`, without slicing it.
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `source` is shorter than the end of the span, and
+    /// [`SpanError::NegativeSize`] if the span isn't [well-formed](Span::is_well_formed).
+    pub fn try_byte_bounds(&self, source: &str) -> Result<(usize, usize), SpanError> {
+        let (char_start, char_end) = self.slice_indexes(source.len())?;
+        let mut indices = source
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(core::iter::once(source.len()));
+
+        let start = indices.nth(char_start).ok_or(SpanError::OutOfBounds)?;
+        // `nth` already consumed the index of `self.start`, so the index of `self.end` is `len - 1` further.
+        let end = match (char_end - char_start).checked_sub(1) {
+            Some(offset) => indices.nth(offset).ok_or(SpanError::OutOfBounds)?,
+            None => start,
+        };
+        Ok((start, end))
+    }
+
+    /// Returns an iterator over the chars of `source` that the span covers, with `start` and `end` corresponding to
+    /// char indexes, paired with their char index in `source`. If `source` is shorter than the end of the span, the
+    /// iterator stops at the end of `source`.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn iter_chars<'a>(
+        &self,
+        source: &'a str,
+    ) -> impl Iterator<Item = (SpanValue, char)> + use<'a> {
+        // Every index is below `self.end`, so it fits in a `SpanValue`.
+        source
+            .chars()
+            .enumerate()
+            .skip(self.start.to_usize().unwrap_or(usize::MAX))
+            .take(self.len().to_usize().unwrap_or(usize::MAX))
+            .map(|(index, char)| (index as SpanValue, char))
+    }
+
+    /// Returns an iterator over the bytes of `bytes` that the span covers, with `start` and `end` corresponding to
+    /// byte offsets, paired with their offset in `bytes`. If `bytes` is shorter than the end of the span, the
+    /// iterator stops at the end of `bytes`.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn iter_bytes<'a>(
+        &self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = (SpanValue, u8)> + use<'a> {
+        // Every offset is below `self.end`, so it fits in a `SpanValue`.
+        bytes
+            .iter()
+            .copied()
+            .enumerate()
+            .skip(self.start.to_usize().unwrap_or(usize::MAX))
+            .take(self.len().to_usize().unwrap_or(usize::MAX))
+            .map(|(index, byte)| (index as SpanValue, byte))
+    }
+
+    /// Returns an iterator over the parts of the span on each line of `source` it touches, with `start` and `end`
+    /// corresponding to char indexes and lines separated by `\n`. The parts don't include the `\n`s, so they stop
+    /// at the end of each line, and a span on a single line is returned unchanged. Past the end of `source`, the
+    /// span is treated as all being on the last line.
+    pub fn split_lines<'a>(&self, source: &'a str) -> impl Iterator<Item = Span> + use<'a> {
+        let span = *self;
+        let mut newlines = self
+            .iter_chars(source)
+            .filter(|&(_, char)| char == '\n')
+            .map(|(index, _)| index);
+        let mut line_start = Some(span.start);
+
+        core::iter::from_fn(move || {
+            let start = line_start?;
+            match newlines.next() {
+                Some(newline) => {
+                    line_start = Some(newline + 1);
+                    Some(Span {
+                        start,
+                        end: newline,
+                    })
+                }
+                None => {
+                    line_start = None;
+                    // Only a span with no `\n`s has an empty part at its end, which is the whole span.
+                    (start < span.end || start == span.start).then_some(Span {
+                        start,
+                        end: span.end,
+                    })
+                }
+            }
+        })
+    }
+
+    /// Gets the 1-based line and column of the start of the span in `source`, with `start` corresponding to a char
+    /// index.
+    ///
+    /// Lines are separated by `\n`. A `\r` isn't counted as a column, so `\r\n` line endings work too. If the span
+    /// starts past the end of `source`, the position of the end of `source` is returned.
+    pub fn line_col(&self, source: &str) -> (usize, usize) {
+        let mut line = 1;
+        let mut column = 1;
+
+        for char in source
+            .chars()
+            .take(self.start.to_usize().unwrap_or(usize::MAX))
+        {
+            match char {
+                '\n' => {
+                    line += 1;
+                    column = 1;
+                }
+                '\r' => {}
+                _ => column += 1,
+            }
+        }
+
+        (line, column)
+    }
+
+    /// Applies the span to `string`, with `start` and `end` corresponding to byte indexes. The span may end at the
+    /// end of `string`.
+    ///
+    /// # Panics
+    /// Panics if `string` is shorter than the end of the span, or if `start` or `end` isn't on a char boundary.
+    pub fn apply_bytes<'a>(&self, string: &'a str) -> &'a str {
+        self.try_apply_bytes(string)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Applies the span to `string`, with `start` and `end` corresponding to byte indexes.
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `string` is shorter than the end of the span,
+    /// [`SpanError::NotCharBoundary`] if `start` or `end` isn't on a char boundary, and [`SpanError::NegativeSize`] if
+    /// the span isn't [well-formed](Span::is_well_formed).
+    pub fn try_apply_bytes<'a>(&self, string: &'a str) -> Result<&'a str, SpanError> {
+        let (start, end) = self.str_byte_indexes(string)?;
+        Ok(&string[start..end])
+    }
+
+    /// Applies the span to `string` like [`apply`](Span::apply), and also returns the text before and after it, as
+    /// `(before, matched, after)`.
+    ///
+    /// # Panics
+    /// Panics if `string` is shorter than the end of the span.
+    pub fn apply_with_context<'a>(&self, string: &'a str) -> (&'a str, &'a str, &'a str) {
+        self.try_apply_with_context(string)
+            .expect("string is too short to have the span applied")
+    }
+
+    /// Applies the span to `string` like [`try_apply`](Span::try_apply), and also returns the text before and after
+    /// it, as `(before, matched, after)`.
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `string` is shorter than the end of the span, and
+    /// [`SpanError::NegativeSize`] if the span isn't [well-formed](Span::is_well_formed).
+    pub fn try_apply_with_context<'a>(
+        &self,
+        string: &'a str,
+    ) -> Result<(&'a str, &'a str, &'a str), SpanError> {
+        let (start, end) = self.try_byte_bounds(string)?;
+        Ok((&string[..start], &string[start..end], &string[end..]))
+    }
+
+    /// Applies the span to `string` like [`apply_bytes`](Span::apply_bytes), and also returns the text before and
+    /// after it, as `(before, matched, after)`.
+    ///
+    /// # Panics
+    /// Panics if `string` is shorter than the end of the span, or if `start` or `end` isn't on a char boundary.
+    pub fn apply_bytes_with_context<'a>(&self, string: &'a str) -> (&'a str, &'a str, &'a str) {
+        self.try_apply_bytes_with_context(string)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Applies the span to `string` like [`try_apply_bytes`](Span::try_apply_bytes), and also returns the text
+    /// before and after it, as `(before, matched, after)`.
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `string` is shorter than the end of the span,
+    /// [`SpanError::NotCharBoundary`] if `start` or `end` isn't on a char boundary, and [`SpanError::NegativeSize`] if
+    /// the span isn't [well-formed](Span::is_well_formed).
+    pub fn try_apply_bytes_with_context<'a>(
+        &self,
+        string: &'a str,
+    ) -> Result<(&'a str, &'a str, &'a str), SpanError> {
+        let (start, end) = self.str_byte_indexes(string)?;
+        Ok((&string[..start], &string[start..end], &string[end..]))
+    }
+
+    /// Applies the span to `bytes`, with `start` and `end` corresponding to byte offsets, like
+    /// [`apply_bytes`](Span::apply_bytes).
+    ///
+    /// # Panics
+    /// Panics if `bytes` is shorter than the end of the span.
+    pub fn apply_slice<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        self.try_apply_slice(bytes)
+            .expect("slice is too short to have the span applied")
+    }
+
+    /// Applies the span to `bytes`, with `start` and `end` corresponding to byte offsets, like
+    /// [`apply_bytes`](Span::apply_bytes).
+    ///
+    /// Returns [`SpanError::OutOfBounds`] if `bytes` is shorter than the end of the span, and
+    /// [`SpanError::NegativeSize`] if the span isn't [well-formed](Span::is_well_formed).
+    pub fn try_apply_slice<'a>(&self, bytes: &'a [u8]) -> Result<&'a [u8], SpanError> {
+        let (start, end) = self.slice_indexes(bytes.len())?;
+        Ok(&bytes[start..end])
+    }
+
+    /// Gets `start` and `end` as indexes into something `len` long, checking that the span ends inside it.
+    fn slice_indexes(&self, len: usize) -> Result<(usize, usize), SpanError> {
+        if !self.is_well_formed() {
+            return Err(SpanError::NegativeSize {
+                start: self.start,
+                end: self.end,
+            });
+        }
+
+        match self.end.to_usize() {
+            // `start` is at most `end`, so it fits in a `usize` too.
+            Some(end) if end <= len => Ok((self.start.to_usize().unwrap_or(end), end)),
+            _ => Err(SpanError::OutOfBounds),
+        }
+    }
+
+    /// Gets `start` and `end` as byte indexes into `string`, checking that the span ends inside it and that both
+    /// are on char boundaries.
+    fn str_byte_indexes(&self, string: &str) -> Result<(usize, usize), SpanError> {
+        let (start, end) = self.slice_indexes(string.len())?;
+        if !string.is_char_boundary(start) || !string.is_char_boundary(end) {
+            return Err(SpanError::NotCharBoundary);
+        }
+        Ok((start, end))
+    }
+}
+
+impl From<Span> for Range<SpanValue> {
+    #[inline(always)]
+    fn from(val: Span) -> Self {
+        val.start..val.end
+    }
+}
+
+impl From<Range<SpanValue>> for Span {
+    #[inline(always)]
+    fn from(value: Range<SpanValue>) -> Self {
+        Self::new_from(value.start, value.end)
+    }
+}
+
+impl RangeBounds<SpanValue> for Span {
+    #[inline(always)]
+    fn start_bound(&self) -> Bound<&SpanValue> {
+        Bound::Included(&self.start)
+    }
+
+    #[inline(always)]
+    fn end_bound(&self) -> Bound<&SpanValue> {
+        Bound::Excluded(&self.end)
+    }
+}
+
+impl RangeBounds<SpanValue> for &Span {
+    #[inline(always)]
+    fn start_bound(&self) -> Bound<&SpanValue> {
+        Bound::Included(&self.start)
+    }
+
+    #[inline(always)]
+    fn end_bound(&self) -> Bound<&SpanValue> {
+        Bound::Excluded(&self.end)
+    }
+}
+
+impl IntoIterator for Span {
+    type Item = SpanValue;
+    type IntoIter = Range<SpanValue>;
+
+    /// Iterates over every index covered by the span, from `start` up to (but not including) `end`.
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.start..self.end
+    }
+}
+
+impl IntoIterator for &Span {
+    type Item = SpanValue;
+    type IntoIter = Range<SpanValue>;
+
+    /// Iterates over every index covered by the span, from `start` up to (but not including) `end`.
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.start..self.end
+    }
+}
+
+impl From<RangeTo<SpanValue>> for Span {
+    /// Converts `..end` into a span starting at 0. Ranges without an end, like `start..` and `..`, have no
+    /// matching `From` impl, since a span always needs an end; use [`Span::from_range_from`] for those.
+    #[inline(always)]
+    fn from(value: RangeTo<SpanValue>) -> Self {
+        Self::from_start(value.end)
+    }
+}
+
+impl From<Span> for (SpanValue, SpanValue) {
+    #[inline(always)]
+    fn from(val: Span) -> Self {
+        (val.start, val.end)
+    }
+}
+
+impl From<(SpanValue, SpanValue)> for Span {
+    /// Converts a `(start, end)` pair into a span.
+    ///
+    /// # Panics
+    /// Panics if start is greater than end, since spans can't have a negative length.
+    #[inline(always)]
+    fn from((start, end): (SpanValue, SpanValue)) -> Self {
+        Self::new_from(start, end)
+    }
+}
+
+impl From<Span> for RangeInclusive<SpanValue> {
+    /// Converts a span into the inclusive range `start..=end - 1`.
+    ///
+    /// # Panics
+    /// Panics if the span is empty, since an empty span has no last value to include.
+    #[inline(always)]
+    fn from(val: Span) -> Self {
+        assert!(
+            !val.is_empty(),
+            "cannot convert an empty span to an inclusive range"
+        );
+        val.start..=(val.end - 1)
+    }
+}
+
+impl From<RangeInclusive<SpanValue>> for Span {
+    /// Converts an inclusive range `start..=end` into the span `start..end + 1`.
+    ///
+    /// # Panics
+    /// Panics if the end of the range is `SpanValue::MAX`, since the end of the span would be out of range, or
+    /// if the range ends more than one value before it starts.
+    #[inline(always)]
+    fn from(value: RangeInclusive<SpanValue>) -> Self {
+        let (start, end) = value.into_inner();
+        let end = end
+            .checked_add(1)
+            .expect("cannot create a span ending after SpanValue::MAX");
+        Self::new_from(start, end)
+    }
+}
+
+impl fmt::Display for Span {
+    /// Writes the span as `start..end`, the same format [`FromStr`] parses.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+impl fmt::LowerHex for Span {
+    /// Writes the span as `start..end` in lowercase hex. Formatting flags like `#` and the width apply to each end.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.start, f)?;
+        f.write_str("..")?;
+        fmt::LowerHex::fmt(&self.end, f)
+    }
+}
+
+impl fmt::UpperHex for Span {
+    /// Writes the span as `start..end` in uppercase hex. Formatting flags like `#` and the width apply to each end.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.start, f)?;
+        f.write_str("..")?;
+        fmt::UpperHex::fmt(&self.end, f)
+    }
+}
+
+impl FromStr for Span {
+    type Err = ParseSpanError;
+
+    /// Parses a span written as `start..end`, like `12..30`.
+    ///
+    /// Surrounding whitespace isn't trimmed, so `" 12..30"` is an invalid integer.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.split_once("..").ok_or(ParseSpanError::MissingSeparator)?;
+        let start = start.parse().map_err(ParseSpanError::InvalidInteger)?;
+        let end = end.parse().map_err(ParseSpanError::InvalidInteger)?;
+
+        if end < start {
+            return Err(ParseSpanError::NegativeSize);
+        }
+
+        Ok(Span { start, end })
+    }
+}
+
+impl PartialEq<Range<SpanValue>> for Span {
+    #[inline(always)]
+    fn eq(&self, other: &Range<SpanValue>) -> bool {
+        self.start == other.start && self.end == other.end
+    }
+}
+
+impl PartialEq<Span> for Range<SpanValue> {
+    #[inline(always)]
+    fn eq(&self, other: &Span) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd for Span {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        dual_order(self.start.cmp(&other.start), self.end.cmp(&other.end))
+    }
+}
+
+/// A span is equal to a point if it contains it, meaning `start <= point < end`, so `span == point` is the same as
+/// [`span.contains_point(point)`](Span::contains_point). An empty span contains no points, so it is never equal to
+/// one.
+impl PartialEq<SpanValue> for Span {
+    #[inline(always)]
+    fn eq(&self, other: &SpanValue) -> bool {
+        self.contains_point(*other)
+    }
+}
+
+/// A span is less than a point if it ends at or before the point, greater than it if it starts after it, and
+/// equal to it if it contains it, so `span < point` checks if the span lies entirely before `point`. An empty span
+/// at a point is less than it, since it ends there.
+impl PartialOrd<SpanValue> for Span {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &SpanValue) -> Option<Ordering> {
+        if self.end <= *other {
+            Some(Ordering::Less)
+        } else if self.start > *other {
+            Some(Ordering::Greater)
+        } else {
+            Some(Ordering::Equal)
+        }
+    }
+}
+
+impl Add<SpanValue> for Span {
+    type Output = Span;
+
+    /// Moves the span up by `rhs`, which is added to both the start and end values.
+    ///
+    /// # Panics
+    /// Panics if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    fn add(self, rhs: SpanValue) -> Span {
+        self.checked_add(rhs)
+            .expect("cannot shift a span out of the range of SpanValue")
+    }
+}
+
+impl AddAssign<SpanValue> for Span {
+    /// Moves the span up by `rhs`, which is added to both the start and end values.
+    ///
+    /// # Panics
+    /// Panics if the end value would overflow `SpanValue`.
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: SpanValue) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<SpanValue> for Span {
+    type Output = Span;
+
+    /// Moves the span back by `rhs`, which is subtracted from both the start and end values.
+    ///
+    /// # Panics
+    /// Panics if the start value would become negative.
+    #[inline(always)]
+    fn sub(self, rhs: SpanValue) -> Span {
+        self.checked_sub(rhs)
+            .expect("cannot shift a span out of the range of SpanValue")
+    }
+}
+
+impl SubAssign<SpanValue> for Span {
+    /// Moves the span back by `rhs`, which is subtracted from both the start and end values.
+    ///
+    /// # Panics
+    /// Panics if the start value would become negative.
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: SpanValue) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Span {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let (a, b) = <(SpanValue, SpanValue)>::arbitrary(u)?;
+        Ok(Span {
+            start: a.min(b),
+            end: a.max(b),
+        })
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <(SpanValue, SpanValue)>::size_hint(depth)
+    }
+}
+
+#[cfg(feature = "ariadne")]
+impl ariadne::Span for Span {
+    type SourceId = ();
+
+    fn source(&self) -> &Self::SourceId {
+        &()
+    }
+
+    fn start(&self) -> usize {
+        self.start.to_usize().expect("span doesn't fit in usize")
+    }
+
+    fn end(&self) -> usize {
+        self.end.to_usize().expect("span doesn't fit in usize")
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Span {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.start.serialize(writer)?;
+        self.end.serialize(writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Span {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let start = SpanValue::deserialize_reader(reader)?;
+        let end = SpanValue::deserialize_reader(reader)?;
+        Span::try_new_from(start, end).map_err(|_| {
+            borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                "cannot create negative-size span",
+            )
+        })
+    }
+}
+
+// SAFETY: `Span` is `repr(C)` and made of two `SpanValue`s, so it has no padding, and all zeroes is `0..0`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Span {}
+
+/// Spans cast from bytes aren't checked, so their end may be before their start.
+///
+/// A `Span` has the same size and alignment as `[SpanValue; 2]`, so the layout depends on its integer type. For
+/// `usize` it also depends on the platform's pointer width, and `u128` is 16-byte aligned on most 64-bit platforms,
+/// so memory-mapped data must be aligned to match.
+// SAFETY: `Span` is `repr(C)` and made of two `SpanValue`s, so it has no padding, every bit pattern is a valid
+// `Span`, and it is `Copy` and `'static`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Span {}
+
+#[cfg(feature = "chumsky")]
+impl chumsky::span::Span for Span {
+    type Context = ();
+    type Offset = SpanValue;
+
+    fn new(_context: Self::Context, range: Range<Self::Offset>) -> Self {
+        range.into()
+    }
+
+    fn context(&self) -> Self::Context {}
+
+    fn start(&self) -> Self::Offset {
+        self.start
+    }
+
+    fn end(&self) -> Self::Offset {
+        self.end
+    }
+}
+
+#[cfg(feature = "codespan")]
+impl Span {
+    /// Converts the span into the `Range<usize>` that `codespan-reporting` labels use.
+    ///
+    /// # Panics
+    /// Panics if either end doesn't fit in a `usize`, like [`as_usize_range`](Span::as_usize_range).
+    pub fn to_label_range(&self) -> Range<usize> {
+        self.as_usize_range()
+    }
+
+    /// Creates a primary `codespan-reporting` label covering the span in the file `file_id`.
+    pub fn primary_label<FileId>(
+        &self,
+        file_id: FileId,
+    ) -> codespan_reporting::diagnostic::Label<FileId> {
+        codespan_reporting::diagnostic::Label::primary(file_id, self.to_label_range())
+    }
+
+    /// Creates a secondary `codespan-reporting` label covering the span in the file `file_id`.
+    pub fn secondary_label<FileId>(
+        &self,
+        file_id: FileId,
+    ) -> codespan_reporting::diagnostic::Label<FileId> {
+        codespan_reporting::diagnostic::Label::secondary(file_id, self.to_label_range())
+    }
+}
+
+#[cfg(feature = "miette")]
+impl From<Span> for miette::SourceSpan {
+    fn from(val: Span) -> Self {
+        let range = val.as_usize_range();
+        miette::SourceSpan::new(range.start.into(), range.len())
+    }
+}
+
+#[cfg(feature = "pest")]
+impl Span {
+    /// Creates a new `Span` from the byte offsets of a `pest` span.
+    ///
+    /// # Panics
+    /// Panics if either offset doesn't fit in a `SpanValue`.
+    pub fn from_pest(span: pest::Span<'_>) -> Span {
+        Self::from_logos(span.start()..span.end())
+    }
+
+    /// Creates a new `Span` from the byte offsets of a `pest` span.
+    ///
+    /// Returns [`SpanError::Overflow`] if either offset doesn't fit in a `SpanValue`.
+    pub fn try_from_pest(span: pest::Span<'_>) -> Result<Span, SpanError> {
+        Self::try_from_logos(span.start()..span.end())
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for Span {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let a = SpanValue::arbitrary(g);
+        let b = SpanValue::arbitrary(g);
+        Span {
+            start: a.min(b),
+            end: a.max(b),
+        }
+    }
+
+    /// Shrinks the span's length, keeping its start, so that it shrinks toward the empty span at `start`.
+    fn shrink(&self) -> alloc::boxed::Box<dyn Iterator<Item = Self>> {
+        let start = self.start;
+        alloc::boxed::Box::new(self.len().shrink().map(move |len| Span {
+            start,
+            end: start + len,
+        }))
+    }
+}
+
+// SAFETY: `verify` only reads the fields of the archived span, which bytecheck has already checked, and fails if its
+// end is before its start.
+#[cfg(feature = "rkyv")]
+unsafe impl<C> rkyv::bytecheck::Verify<C> for ArchivedSpan
+where
+    C: rkyv::rancor::Fallible + ?Sized,
+    C::Error: rkyv::rancor::Source,
+{
+    fn verify(&self, _context: &mut C) -> Result<(), C::Error> {
+        let start = rkyv::api::deserialize_using::<SpanValue, _, C::Error>(&self.start, &mut ())?;
+        let end = rkyv::api::deserialize_using::<SpanValue, _, C::Error>(&self.end, &mut ())?;
+        Span::try_new_from(start, end)
+            .map(|_| ())
+            .map_err(<C::Error as rkyv::rancor::Source>::new)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Span {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Span")]
+        struct RawSpan {
+            start: SpanValue,
+            end: SpanValue,
+        }
+
+        let RawSpan { start, end } = RawSpan::deserialize(deserializer)?;
+        Span::try_new_from(start, end).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,67 @@
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The integer operations spans are built on. This is implemented for all unsigned primitive integers, which are the
+/// integer types a [`GenericSpan`](crate::GenericSpan) can have, and [`SpanValue`](crate::SpanValue) is always one
+/// of them.
+///
+/// This trait is sealed, so it can't be implemented outside of this crate.
+pub trait SpanInt: Copy + Ord + sealed::Sealed {
+    /// The signed integer type of the same width, used to move spans in either direction
+    type Delta: Copy + Ord;
+
+    /// The value `0`
+    const ZERO: Self;
+    /// The value `1`
+    const ONE: Self;
+
+    /// Adds `rhs` to `self`, returning `None` on overflow.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Subtracts `rhs` from `self`, returning `None` on underflow.
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// Converts `self` into a `usize`, returning `None` if it doesn't fit.
+    fn to_usize(self) -> Option<usize>;
+
+    /// Converts `value` into `Self`, returning `None` if it doesn't fit.
+    fn from_usize(value: usize) -> Option<Self>;
+}
+
+macro_rules! impl_span_int {
+    ($($int:ty => $delta:ty),*) => {
+        $(
+            impl sealed::Sealed for $int {}
+
+            impl SpanInt for $int {
+                type Delta = $delta;
+
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline(always)]
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$int>::checked_add(self, rhs)
+                }
+
+                #[inline(always)]
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$int>::checked_sub(self, rhs)
+                }
+
+                #[inline(always)]
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+
+                #[inline(always)]
+                fn from_usize(value: usize) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_span_int!(u8 => i8, u16 => i16, u32 => i32, u64 => i64, u128 => i128, usize => isize);
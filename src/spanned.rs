@@ -0,0 +1,65 @@
+use crate::Span;
+
+/// A value paired with the `Span` it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    /// The value
+    pub value: T,
+    /// The area the value covers
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// Creates a new `Spanned` from a value and its span.
+    #[inline(always)]
+    pub const fn new(value: T, span: Span) -> Self {
+        Self { value, span }
+    }
+
+    /// Transforms the value with `f`, keeping the same span.
+    #[inline(always)]
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Spanned<U> {
+        Spanned {
+            value: f(self.value),
+            span: self.span,
+        }
+    }
+
+    /// Converts from `&Spanned<T>` to `Spanned<&T>`.
+    #[inline(always)]
+    pub const fn as_ref(&self) -> Spanned<&T> {
+        Spanned {
+            value: &self.value,
+            span: self.span,
+        }
+    }
+
+    /// Converts from `&mut Spanned<T>` to `Spanned<&mut T>`.
+    #[inline(always)]
+    pub const fn as_mut(&mut self) -> Spanned<&mut T> {
+        Spanned {
+            value: &mut self.value,
+            span: self.span,
+        }
+    }
+}
+
+/// A `Span` paired with the id of the source it belongs to, like a file path or index.
+///
+/// With the `ariadne` feature, this implements `ariadne::Span` with `S` as the source id, for reports that cover
+/// multiple sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpannedSource<S> {
+    /// The id of the source the span belongs to
+    pub source: S,
+    /// The area of the source
+    pub span: Span,
+}
+
+impl<S> SpannedSource<S> {
+    /// Creates a new `SpannedSource` from a source id and a span within that source.
+    #[inline(always)]
+    pub const fn new(source: S, span: Span) -> Self {
+        Self { source, span }
+    }
+}
@@ -0,0 +1,32 @@
+//! [`mod@proptest`] strategies for generating valid spans.
+
+use proptest::prelude::*;
+
+use crate::{Span, SpanValue};
+
+/// Builds a span from two endpoints in either order.
+fn from_endpoints((a, b): (SpanValue, SpanValue)) -> Span {
+    Span {
+        start: a.min(b),
+        end: a.max(b),
+    }
+}
+
+/// Generates any valid span.
+pub fn any_span() -> impl Strategy<Value = Span> {
+    (any::<SpanValue>(), any::<SpanValue>()).prop_map(from_endpoints)
+}
+
+/// Generates valid spans that end at or before `max`, like spans within a source of length `max`.
+pub fn span_within(max: SpanValue) -> impl Strategy<Value = Span> {
+    (0..=max, 0..=max).prop_map(from_endpoints)
+}
+
+impl Arbitrary for Span {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Span>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        any_span().boxed()
+    }
+}